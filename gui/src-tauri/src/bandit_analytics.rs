@@ -0,0 +1,665 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::evolution_history::{ArmPull, BanditSnapshot, EvolutionHistory};
+use crate::schemas::{FieldError, IpcError, SchemaViolation};
+use crate::util::{mean, now_secs};
+use crate::{AppState, BanditStatus, MutateRequest};
+
+/// File (inside the app data dir) holding the bandit policy
+const POLICY_FILE: &str = "bandit_policy.json";
+
+/// Exploration coefficient the backend bandit starts with
+const DEFAULT_EXPLORATION_RATE: f64 = 1.0;
+
+/// z for a two-sided 95% interval
+const Z_95: f64 = 1.96;
+
+/// Pulls making up the "recent" window for means and regret
+const RECENT_WINDOW: usize = 20;
+
+/// Pulls needed before a reward trend is judged
+const MIN_TREND_PULLS: usize = 5;
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// Prior belief about an arm's reward, worth `weight` observed pulls
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ArmPrior {
+    pub mean: f64,
+    pub weight: f64,
+}
+
+/// Exploration rate is the backend's UCB beta, re-sent whenever the backend
+/// may have restarted. Enabled arms and priors exist only on this side: they
+/// steer auto mutations made through the `mutate_workflow` command and the
+/// analytics, while mutations sent to the backend any other way still let it
+/// pick from every arm with its own statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditPolicy {
+    pub exploration_rate: f64,
+    /// `None` leaves every arm enabled; only honoured by `mutate_workflow`
+    pub enabled_arms: Option<Vec<String>>,
+    /// Only used by `mutate_workflow` arm choice and the analytics
+    pub priors: BTreeMap<String, ArmPrior>,
+    pub updated_at: Option<f64>,
+}
+
+impl Default for BanditPolicy {
+    fn default() -> Self {
+        Self {
+            exploration_rate: DEFAULT_EXPLORATION_RATE,
+            enabled_arms: None,
+            priors: BTreeMap::new(),
+            updated_at: None,
+        }
+    }
+}
+
+/// Fields left out are kept as they are
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BanditPolicyUpdate {
+    pub exploration_rate: Option<f64>,
+    /// An empty list re-enables every arm
+    pub enabled_arms: Option<Vec<String>>,
+    pub priors: Option<BTreeMap<String, ArmPrior>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditPolicyResult {
+    pub policy: BanditPolicy,
+    /// UCB beta the backend reported after the update
+    pub backend_ucb_beta: Option<f64>,
+    /// Updated fields the backend bandit applied
+    pub backend_applied: Vec<String>,
+    /// Updated fields kept on this side only, honoured by `mutate_workflow`
+    pub local_only: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardTrend {
+    Improving,
+    Declining,
+    /// The slope's 95% interval contains zero
+    Flat,
+    Insufficient,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendPoint {
+    pub created_at: f64,
+    pub pulls: i64,
+    pub mean_reward: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmStats {
+    pub arm: String,
+    pub enabled: bool,
+    pub prior: Option<ArmPrior>,
+    pub pulls: usize,
+    pub evaluated_pulls: usize,
+    pub mean_reward: Option<f64>,
+    pub std_dev: Option<f64>,
+    /// 95% interval for the mean reward (normal approximation)
+    pub ci_low: Option<f64>,
+    pub ci_high: Option<f64>,
+    /// Mean reward with the prior blended in
+    pub posterior_mean: Option<f64>,
+    pub recent_mean: Option<f64>,
+    /// Reward change per day, by least squares over pull times
+    pub trend_slope: Option<f64>,
+    pub trend: RewardTrend,
+    /// Latest counters from the backend bandit
+    pub backend_pulls: Option<i64>,
+    pub backend_mean_reward: Option<f64>,
+    pub backend_series: Vec<BackendPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegretPoint {
+    pub pull: usize,
+    pub created_at: f64,
+    pub arm: String,
+    pub cumulative_regret: f64,
+}
+
+/// Arm statistics over recorded mutations. Rewards are score deltas: the
+/// variant's latest evaluation delta, else the delta reported by the mutation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditAnalytics {
+    pub policy: BanditPolicy,
+    pub arms: Vec<ArmStats>,
+    pub total_pulls: usize,
+    /// Arm with the highest posterior mean
+    pub best_arm: Option<String>,
+    /// Estimated regret against always pulling `best_arm`
+    pub cumulative_regret: f64,
+    pub mean_regret: Option<f64>,
+    /// Falls towards zero as the bandit settles on the best arm
+    pub recent_mean_regret: Option<f64>,
+    pub regret_curve: Vec<RegretPoint>,
+}
+
+/// Sample standard deviation
+fn std_dev(values: &[f64]) -> Option<f64> {
+    let m = mean(values)?;
+    (values.len() > 1).then(|| {
+        let ss: f64 = values.iter().map(|v| (v - m).powi(2)).sum();
+        (ss / (values.len() - 1) as f64).sqrt()
+    })
+}
+
+/// Least-squares slope of reward over time (per day), and its 95% interval
+/// half-width
+fn trend_slope(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    if points.len() < MIN_TREND_PULLS {
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_t = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_r = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_t).powi(2)).sum();
+    if sxx <= f64::EPSILON {
+        return None;
+    }
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_t) * (p.1 - mean_r)).sum();
+    let slope = sxy / sxx;
+    let intercept = mean_r - slope * mean_t;
+    let sse: f64 = points
+        .iter()
+        .map(|p| (p.1 - intercept - slope * p.0).powi(2))
+        .sum();
+    let se = (sse / (n - 2.0) / sxx).sqrt();
+    Some((slope * SECS_PER_DAY, Z_95 * se * SECS_PER_DAY))
+}
+
+fn backend_series(snapshots: &[BanditSnapshot], arm: &str) -> Vec<BackendPoint> {
+    snapshots
+        .iter()
+        .filter(|s| s.arm == arm)
+        .map(|s| BackendPoint {
+            created_at: s.created_at,
+            pulls: s.pulls,
+            mean_reward: (s.pulls > 0).then(|| s.total_reward / s.pulls as f64),
+        })
+        .collect()
+}
+
+impl BanditPolicy {
+    fn is_enabled(&self, arm: &str) -> bool {
+        self.enabled_arms
+            .as_ref()
+            .is_none_or(|arms| arms.iter().any(|a| a == arm))
+    }
+
+    fn posterior_mean(&self, arm: &str, rewards: &[f64]) -> Option<f64> {
+        let prior = self.priors.get(arm).filter(|p| p.weight > 0.0);
+        match prior {
+            Some(p) => Some(
+                (p.mean * p.weight + rewards.iter().sum::<f64>())
+                    / (p.weight + rewards.len() as f64),
+            ),
+            None => mean(rewards),
+        }
+    }
+
+    fn apply(&mut self, update: BanditPolicyUpdate) -> Result<(), SchemaViolation> {
+        let mut errors = Vec::new();
+
+        if let Some(rate) = update.exploration_rate {
+            if !rate.is_finite() || rate < 0.0 {
+                errors.push(FieldError::new(
+                    "exploration_rate",
+                    format!("{} must be a non-negative number", rate),
+                ));
+            }
+        }
+        if let Some(arms) = &update.enabled_arms {
+            for (i, arm) in arms.iter().enumerate() {
+                if arm.trim().is_empty() {
+                    errors.push(FieldError::new(&format!("enabled_arms[{}]", i), "is empty"));
+                }
+            }
+        }
+        for (arm, prior) in update.priors.iter().flatten() {
+            if !prior.mean.is_finite() {
+                errors.push(FieldError::new(
+                    &format!("priors.{}.mean", arm),
+                    "must be a finite number",
+                ));
+            }
+            if !prior.weight.is_finite() || prior.weight < 0.0 {
+                errors.push(FieldError::new(
+                    &format!("priors.{}.weight", arm),
+                    format!("{} must be a non-negative number", prior.weight),
+                ));
+            }
+        }
+        if !errors.is_empty() {
+            return Err(SchemaViolation::new("bandit_policy", errors));
+        }
+
+        if let Some(rate) = update.exploration_rate {
+            self.exploration_rate = rate;
+        }
+        if let Some(arms) = update.enabled_arms {
+            let arms: BTreeSet<String> = arms.into_iter().collect();
+            self.enabled_arms = (!arms.is_empty()).then(|| arms.into_iter().collect());
+        }
+        if let Some(priors) = update.priors {
+            self.priors = priors;
+        }
+        self.updated_at = Some(now_secs());
+        Ok(())
+    }
+
+    /// UCB1 over the enabled arms, mirroring the backend's selection: unpulled
+    /// arms first, then mean reward plus the exploration bonus
+    fn choose_arm(&self, pulls: &[ArmPull]) -> Option<String> {
+        let arms = self.enabled_arms.as_ref()?;
+        let mut rewards: BTreeMap<&str, Vec<f64>> =
+            arms.iter().map(|a| (a.as_str(), Vec::new())).collect();
+        for pull in pulls {
+            if let Some(r) = rewards.get_mut(pull.arm.as_str()) {
+                r.push(pull.delta_score);
+            }
+        }
+
+        if let Some((arm, _)) = rewards
+            .iter()
+            .find(|(arm, r)| r.is_empty() && !self.priors.contains_key(**arm))
+        {
+            return Some(arm.to_string());
+        }
+
+        let total: f64 = rewards
+            .values()
+            .map(|r| r.len() as f64)
+            .sum::<f64>()
+            .max(1.0);
+        rewards
+            .iter()
+            .map(|(arm, r)| {
+                let weight = self.priors.get(*arm).map_or(0.0, |p| p.weight);
+                let n = (r.len() as f64 + weight).max(1.0);
+                let value = self.posterior_mean(arm, r).unwrap_or(0.0)
+                    + self.exploration_rate * (2.0 * total.ln().max(0.0) / n).sqrt();
+                (*arm, value)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(arm, _)| arm.to_string())
+    }
+}
+
+/// Compute per-arm statistics and regret from local pulls and backend snapshots
+pub fn analyze(
+    policy: &BanditPolicy,
+    pulls: &[ArmPull],
+    snapshots: &[BanditSnapshot],
+) -> BanditAnalytics {
+    let mut names: BTreeSet<&str> = pulls.iter().map(|p| p.arm.as_str()).collect();
+    names.extend(snapshots.iter().map(|s| s.arm.as_str()));
+    names.extend(policy.priors.keys().map(String::as_str));
+    names.extend(policy.enabled_arms.iter().flatten().map(String::as_str));
+
+    let mut arms = Vec::new();
+    let mut posterior: BTreeMap<&str, f64> = BTreeMap::new();
+    for arm in names {
+        let arm_pulls: Vec<&ArmPull> = pulls.iter().filter(|p| p.arm == arm).collect();
+        let rewards: Vec<f64> = arm_pulls.iter().map(|p| p.delta_score).collect();
+        let mean_reward = mean(&rewards);
+        let std = std_dev(&rewards);
+        let half_width = std.map(|s| Z_95 * s / (rewards.len() as f64).sqrt());
+        let posterior_mean = policy.posterior_mean(arm, &rewards);
+        if let Some(m) = posterior_mean {
+            posterior.insert(arm, m);
+        }
+
+        let points: Vec<(f64, f64)> = arm_pulls
+            .iter()
+            .map(|p| (p.created_at, p.delta_score))
+            .collect();
+        let slope = trend_slope(&points);
+        let trend = match slope {
+            None => RewardTrend::Insufficient,
+            Some((s, h)) if s - h > 0.0 => RewardTrend::Improving,
+            Some((s, h)) if s + h < 0.0 => RewardTrend::Declining,
+            Some(_) => RewardTrend::Flat,
+        };
+
+        let series = backend_series(snapshots, arm);
+        let latest = series.last();
+        arms.push(ArmStats {
+            arm: arm.to_string(),
+            enabled: policy.is_enabled(arm),
+            prior: policy.priors.get(arm).copied(),
+            pulls: rewards.len(),
+            evaluated_pulls: arm_pulls.iter().filter(|p| p.evaluated).count(),
+            mean_reward,
+            std_dev: std,
+            ci_low: mean_reward.zip(half_width).map(|(m, h)| m - h),
+            ci_high: mean_reward.zip(half_width).map(|(m, h)| m + h),
+            posterior_mean,
+            recent_mean: mean(&rewards[rewards.len().saturating_sub(RECENT_WINDOW)..]),
+            trend_slope: slope.map(|(s, _)| s),
+            trend,
+            backend_pulls: latest.map(|p| p.pulls),
+            backend_mean_reward: latest.and_then(|p| p.mean_reward),
+            backend_series: series,
+        });
+    }
+
+    let best = posterior
+        .iter()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(arm, m)| (arm.to_string(), *m));
+
+    let mut cumulative_regret = 0.0;
+    let mut regrets = Vec::new();
+    let mut regret_curve = Vec::new();
+    if let Some((_, best_mean)) = &best {
+        for (i, pull) in pulls.iter().enumerate() {
+            let regret = best_mean - posterior.get(pull.arm.as_str()).copied().unwrap_or(0.0);
+            cumulative_regret += regret;
+            regrets.push(regret);
+            regret_curve.push(RegretPoint {
+                pull: i + 1,
+                created_at: pull.created_at,
+                arm: pull.arm.clone(),
+                cumulative_regret,
+            });
+        }
+    }
+
+    BanditAnalytics {
+        policy: policy.clone(),
+        arms,
+        total_pulls: pulls.len(),
+        best_arm: best.map(|(arm, _)| arm),
+        cumulative_regret,
+        mean_regret: mean(&regrets),
+        recent_mean_regret: mean(&regrets[regrets.len().saturating_sub(RECENT_WINDOW)..]),
+        regret_curve,
+    }
+}
+
+/// Fetch the backend bandit counters
+pub async fn fetch_bandit_status(backend_base_url: &str) -> Result<BanditStatus, String> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/bandit/status", backend_base_url);
+
+    match client.get(&url).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<BanditStatus>()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e)),
+        _ => Err("Failed to get bandit status".to_string()),
+    }
+}
+
+/// Set the backend bandit's UCB beta, returning the value it reports back
+async fn push_exploration_rate(backend_base_url: &str, rate: f64) -> Result<Option<f64>, String> {
+    let response = reqwest::Client::new()
+        .patch(format!("{}/bandit/policy", backend_base_url))
+        .json(&json!({ "ucb_beta": rate }))
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend error: {}", response.status()));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    Ok(body.get("ucb_beta").and_then(Value::as_f64))
+}
+
+/// The bandit policy, persisted locally and pushed to the backend
+#[derive(Clone)]
+pub struct BanditPolicyManager {
+    path: Option<PathBuf>,
+    policy: Arc<Mutex<BanditPolicy>>,
+    /// Held for a whole update or sync so backend pushes and saves stay in order
+    updating: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl BanditPolicyManager {
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let path = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(POLICY_FILE));
+        let policy = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            policy: Arc::new(Mutex::new(policy)),
+            updating: Arc::new(tokio::sync::Mutex::new(())),
+        }
+    }
+
+    fn persist(&self, policy: &BanditPolicy) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let raw = serde_json::to_string_pretty(policy).map_err(|e| e.to_string())?;
+        std::fs::write(path, raw).map_err(|e| format!("Failed to save bandit policy: {}", e))
+    }
+
+    pub fn policy(&self) -> BanditPolicy {
+        self.policy.lock().unwrap().clone()
+    }
+
+    /// Validate and apply `update`, sending the exploration rate to the
+    /// backend first so a rejected PATCH leaves the local policy untouched
+    pub async fn update(
+        &self,
+        backend_base_url: &str,
+        update: BanditPolicyUpdate,
+    ) -> Result<BanditPolicyResult, IpcError> {
+        let _updating = self.updating.lock().await;
+        let mut policy = self.policy();
+        policy.apply(update.clone())?;
+
+        let mut backend_ucb_beta = None;
+        let mut backend_applied = Vec::new();
+        if let Some(rate) = update.exploration_rate {
+            backend_ucb_beta = push_exploration_rate(backend_base_url, rate).await?;
+            backend_applied.push("exploration_rate".to_string());
+        }
+        let local_only = [
+            ("enabled_arms", update.enabled_arms.is_some()),
+            ("priors", update.priors.is_some()),
+        ]
+        .into_iter()
+        .filter(|(_, set)| *set)
+        .map(|(field, _)| field.to_string())
+        .collect();
+
+        *self.policy.lock().unwrap() = policy.clone();
+        self.persist(&policy)?;
+        Ok(BanditPolicyResult {
+            policy,
+            backend_ucb_beta,
+            backend_applied,
+            local_only,
+        })
+    }
+
+    /// Re-send a changed exploration rate, which the backend forgets when it
+    /// restarts. A policy that was never updated leaves the backend default.
+    pub async fn sync_backend(&self, backend_base_url: &str) -> Result<(), String> {
+        let _updating = self.updating.lock().await;
+        let policy = self.policy();
+        if policy.updated_at.is_none() {
+            return Ok(());
+        }
+        push_exploration_rate(backend_base_url, policy.exploration_rate)
+            .await
+            .map(|_| ())
+    }
+
+    /// The backend bandit knows nothing of disabled arms, so while any are
+    /// disabled, auto mutations get an explicit arm chosen here
+    pub fn resolve_arm(&self, history: &EvolutionHistory, request: &mut MutateRequest) {
+        let auto = request.arm.as_deref().is_none_or(|arm| arm == "auto");
+        let policy = self.policy();
+        if !auto || policy.enabled_arms.is_none() {
+            return;
+        }
+        match history.arm_pulls(None, None) {
+            Ok(pulls) => {
+                if let Some(arm) = policy.choose_arm(&pulls) {
+                    request.arm = Some(arm);
+                }
+            }
+            Err(e) => eprintln!("[Unity] {}", e),
+        }
+    }
+}
+
+/// Tauri commands for the bandit policy and arm analytics
+#[tauri::command]
+pub async fn get_bandit_policy(
+    manager: tauri::State<'_, BanditPolicyManager>,
+) -> Result<BanditPolicy, String> {
+    Ok(manager.policy())
+}
+
+/// Only the exploration rate reaches the backend; the result lists which
+/// updated fields it applied and which stay local
+#[tauri::command]
+pub async fn update_bandit_policy(
+    state: tauri::State<'_, AppState>,
+    manager: tauri::State<'_, BanditPolicyManager>,
+    update: BanditPolicyUpdate,
+) -> Result<BanditPolicyResult, IpcError> {
+    manager.update(&state.backend_base_url, update).await
+}
+
+/// Refreshes the backend snapshot and exploration rate when reachable, then
+/// analyzes the history
+#[tauri::command]
+pub async fn get_bandit_analytics(
+    state: tauri::State<'_, AppState>,
+    manager: tauri::State<'_, BanditPolicyManager>,
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+    since: Option<f64>,
+) -> Result<BanditAnalytics, String> {
+    if let Ok(status) = fetch_bandit_status(&state.backend_base_url).await {
+        if let Err(e) = history.record_bandit_status(&status) {
+            eprintln!("[Unity] {}", e);
+        }
+        if let Err(e) = manager.sync_backend(&state.backend_base_url).await {
+            eprintln!("[Unity] Failed to sync bandit policy: {}", e);
+        }
+    }
+
+    let pulls = history.arm_pulls(goal.as_deref(), since)?;
+    let snapshots = history.bandit_snapshots(since)?;
+    Ok(analyze(&manager.policy(), &pulls, &snapshots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pull(arm: &str, delta_score: f64, created_at: f64) -> ArmPull {
+        ArmPull {
+            variant_id: format!("{}-{}", arm, created_at),
+            goal: "summarise".to_string(),
+            arm: arm.to_string(),
+            delta_score,
+            evaluated: true,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn trend_slope_is_reported_per_day() {
+        let points: Vec<(f64, f64)> = (0..6)
+            .map(|day| (day as f64 * SECS_PER_DAY, 0.1 * day as f64))
+            .collect();
+        let (slope, half_width) = trend_slope(&points).unwrap();
+
+        assert!((slope - 0.1).abs() < 1e-9);
+        assert!(half_width < 1e-9);
+    }
+
+    #[test]
+    fn trend_slope_needs_enough_pulls_spread_over_time() {
+        let points = [(0.0, 0.1), (1.0, 0.2), (2.0, 0.3), (3.0, 0.4)];
+        assert!(trend_slope(&points).is_none());
+
+        let simultaneous = [(5.0, 0.1); MIN_TREND_PULLS];
+        assert!(trend_slope(&simultaneous).is_none());
+    }
+
+    #[test]
+    fn noisy_rewards_without_a_direction_are_flat() {
+        let pulls: Vec<ArmPull> = [0.3, -0.3, 0.3, -0.3, 0.3, -0.3]
+            .iter()
+            .enumerate()
+            .map(|(i, r)| pull("crossover", *r, i as f64 * SECS_PER_DAY))
+            .collect();
+        let analytics = analyze(&BanditPolicy::default(), &pulls, &[]);
+
+        assert_eq!(analytics.arms[0].trend, RewardTrend::Flat);
+    }
+
+    #[test]
+    fn regret_accumulates_against_the_best_arm() {
+        let pulls = [
+            pull("mutate", 1.0, 1.0),
+            pull("crossover", 0.0, 2.0),
+            pull("mutate", 1.0, 3.0),
+            pull("crossover", 0.0, 4.0),
+        ];
+        let analytics = analyze(&BanditPolicy::default(), &pulls, &[]);
+
+        assert_eq!(analytics.best_arm.as_deref(), Some("mutate"));
+        assert_eq!(analytics.cumulative_regret, 2.0);
+        assert_eq!(analytics.mean_regret, Some(0.5));
+        let curve: Vec<f64> = analytics
+            .regret_curve
+            .iter()
+            .map(|p| p.cumulative_regret)
+            .collect();
+        assert_eq!(curve, [0.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn priors_can_change_the_best_arm() {
+        let pulls = [pull("mutate", 1.0, 1.0), pull("crossover", 0.0, 2.0)];
+        let mut policy = BanditPolicy::default();
+        policy.priors.insert(
+            "crossover".to_string(),
+            ArmPrior {
+                mean: 3.0,
+                weight: 2.0,
+            },
+        );
+        let analytics = analyze(&policy, &pulls, &[]);
+
+        // crossover: (3.0 * 2 + 0.0) / 3 = 2.0, so the mutate pull costs 1.0
+        assert_eq!(analytics.best_arm.as_deref(), Some("crossover"));
+        assert_eq!(analytics.cumulative_regret, 1.0);
+    }
+}
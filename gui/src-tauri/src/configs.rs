@@ -0,0 +1,124 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// Overrides where the shared `configs/` directory is looked up
+pub(crate) const CONFIG_DIR_ENV: &str = "UNITY_CONFIG_DIR";
+
+/// Candidate locations of `configs/`, relative to the working directory
+/// (repo root, `gui/`, or `gui/src-tauri/` during development)
+const CONFIG_DIR_CANDIDATES: &[&str] = &["configs", "../configs", "../../configs"];
+
+/// Resolve a file inside the shared `configs/` directory
+pub fn config_path(file: &str) -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var(CONFIG_DIR_ENV) {
+        return Some(PathBuf::from(dir).join(file));
+    }
+
+    CONFIG_DIR_CANDIDATES
+        .iter()
+        .map(|dir| PathBuf::from(dir).join(file))
+        .find(|path| path.is_file())
+}
+
+/// Load and parse a YAML file from `configs/`
+pub fn load_config<T: DeserializeOwned>(file: &str) -> Result<T, String> {
+    let path = config_path(file).ok_or_else(|| format!("Config file not found: {}", file))?;
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    serde_yaml::from_str(&raw).map_err(|e| format!("Invalid {}: {}", path.display(), e))
+}
+
+/// Per-model resource limits from `budget.yaml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelLimit {
+    #[serde(default)]
+    pub max_context: Option<u64>,
+    #[serde(default)]
+    pub estimated_ram_gb: Option<f64>,
+}
+
+/// The parts of `configs/budget.yaml` the shell acts on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub max_concurrency: Option<usize>,
+    #[serde(rename = "MODEL_LIMITS", default)]
+    pub model_limits: HashMap<String, ModelLimit>,
+}
+
+impl BudgetConfig {
+    pub const FILE: &'static str = "budget.yaml";
+
+    pub fn load() -> Result<Self, String> {
+        load_config(Self::FILE)
+    }
+}
+
+/// `CACHE` section of `eval.yaml`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCacheConfig {
+    #[serde(default = "EvalCacheConfig::default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "EvalCacheConfig::default_max_entries")]
+    pub max_entries: usize,
+    /// Entries older than this are no longer fresh
+    #[serde(default = "EvalCacheConfig::default_ttl_seconds")]
+    pub ttl_seconds: u64,
+    /// Expired entries are kept this long as an offline fallback (shell only)
+    #[serde(default = "EvalCacheConfig::default_stale_max_age_seconds")]
+    pub stale_max_age_seconds: u64,
+}
+
+impl EvalCacheConfig {
+    fn default_enabled() -> bool {
+        true
+    }
+
+    fn default_max_entries() -> usize {
+        1000
+    }
+
+    fn default_ttl_seconds() -> u64 {
+        3600
+    }
+
+    fn default_stale_max_age_seconds() -> u64 {
+        7 * 24 * 3600
+    }
+}
+
+impl Default for EvalCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: Self::default_enabled(),
+            max_entries: Self::default_max_entries(),
+            ttl_seconds: Self::default_ttl_seconds(),
+            stale_max_age_seconds: Self::default_stale_max_age_seconds(),
+        }
+    }
+}
+
+/// The parts of `configs/eval.yaml` the shell acts on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvalConfig {
+    /// Heuristic scores below this are rejected without the LLM judge
+    #[serde(rename = "TAU_LOW", default)]
+    pub tau_low: f64,
+    /// Heuristic scores above this are accepted without the LLM judge
+    #[serde(rename = "TAU_HIGH", default)]
+    pub tau_high: f64,
+    #[serde(rename = "RUBRIC_WEIGHTS", default)]
+    pub rubric_weights: BTreeMap<String, f64>,
+    #[serde(rename = "CACHE", default)]
+    pub cache: EvalCacheConfig,
+}
+
+impl EvalConfig {
+    pub const FILE: &'static str = "eval.yaml";
+
+    pub fn load() -> Result<Self, String> {
+        load_config(Self::FILE)
+    }
+}
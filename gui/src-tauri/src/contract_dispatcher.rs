@@ -0,0 +1,711 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{watch, RwLock};
+use tokio::task::{self, JoinSet};
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::configs::BudgetConfig;
+use crate::schemas::{
+    self, AssignedAgent, FieldError, IpcError, SchemaViolation, TaskContract, Validate,
+};
+use crate::util::{now_secs, panic_message};
+use crate::AppState;
+
+/// Concurrency used when budget.yaml does not set `max_concurrency`
+const DEFAULT_MAX_CONCURRENCY: usize = 2;
+
+/// Upper bound for a single task when it has no deadline
+const TASK_TIMEOUT_SECS: u64 = 600;
+
+/// Batches kept for inspection once finished
+const MAX_FINISHED_BATCHES: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    /// Waiting on dependencies or a free slot
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    /// Deadline passed before or while running
+    TimedOut,
+    /// A dependency did not succeed
+    Skipped,
+    Cancelled,
+}
+
+impl TaskState {
+    fn is_finished(self) -> bool {
+        !matches!(self, Self::Pending | Self::Running)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub task_id: String,
+    pub assigned_agent: AssignedAgent,
+    pub dependencies: Vec<String>,
+    pub state: TaskState,
+    /// Absolute deadline (unix seconds). The contract's `deadline` is read as
+    /// seconds after the batch is dispatched, so this is dispatch time plus it.
+    pub deadline_at: Option<f64>,
+    pub started_at: Option<f64>,
+    pub finished_at: Option<f64>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContractBatch {
+    pub id: String,
+    pub created_at: f64,
+    pub finished_at: Option<f64>,
+    pub max_concurrency: usize,
+    /// Tasks in a valid execution (topological) order
+    pub tasks: Vec<TaskRecord>,
+}
+
+/// Payload of `unity:contract_task`
+#[derive(Debug, Clone, Serialize)]
+struct TaskEvent<'a> {
+    batch_id: &'a str,
+    task: &'a TaskRecord,
+}
+
+fn violation(errors: Vec<FieldError>) -> SchemaViolation {
+    SchemaViolation::new(TaskContract::SCHEMA, errors)
+}
+
+/// Validate every contract, naming offending fields by their index in the batch
+fn parse_contracts(contracts: Vec<Value>) -> Result<Vec<TaskContract>, SchemaViolation> {
+    let mut parsed = Vec::with_capacity(contracts.len());
+    let mut errors = Vec::new();
+
+    for (i, contract) in contracts.into_iter().enumerate() {
+        match schemas::parse::<TaskContract>(contract) {
+            Ok(contract) => parsed.push(contract),
+            Err(violation) => errors.extend(violation.errors.into_iter().map(|e| FieldError {
+                field: format!("[{}].{}", i, e.field),
+                message: e.message,
+            })),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(violation(errors))
+    }
+}
+
+/// Check ids and dependencies, returning the contracts in topological order
+fn order_contracts(contracts: Vec<TaskContract>) -> Result<Vec<TaskContract>, SchemaViolation> {
+    let mut errors = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (i, contract) in contracts.iter().enumerate() {
+        if index.insert(contract.task_id.clone(), i).is_some() {
+            errors.push(FieldError {
+                field: format!("[{}].task_id", i),
+                message: format!("duplicate task id {:?}", contract.task_id),
+            });
+        }
+    }
+    for (i, contract) in contracts.iter().enumerate() {
+        for (j, dependency) in contract.dependencies.iter().enumerate() {
+            let message = if dependency == &contract.task_id {
+                "task depends on itself"
+            } else if !index.contains_key(dependency) {
+                "unknown task id"
+            } else {
+                continue;
+            };
+            errors.push(FieldError {
+                field: format!("[{}].dependencies[{}]", i, j),
+                message: format!("{} ({:?})", message, dependency),
+            });
+        }
+    }
+    if !errors.is_empty() {
+        return Err(violation(errors));
+    }
+
+    // Kahn's algorithm; whatever cannot be ordered sits on a cycle
+    let mut remaining: Vec<usize> = contracts.iter().map(|c| c.dependencies.len()).collect();
+    let mut ready: Vec<usize> = (0..contracts.len())
+        .filter(|i| remaining[*i] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(contracts.len());
+    while let Some(i) = ready.pop() {
+        order.push(i);
+        for (j, contract) in contracts.iter().enumerate() {
+            let edges = contract
+                .dependencies
+                .iter()
+                .filter(|d| **d == contracts[i].task_id)
+                .count();
+            if edges > 0 {
+                remaining[j] -= edges;
+                if remaining[j] == 0 {
+                    ready.push(j);
+                }
+            }
+        }
+    }
+
+    if order.len() < contracts.len() {
+        let cyclic: Vec<String> = (0..contracts.len())
+            .filter(|i| !order.contains(i))
+            .map(|i| contracts[i].task_id.clone())
+            .collect();
+        return Err(violation(vec![FieldError {
+            field: "dependencies".to_string(),
+            message: format!("dependency cycle among tasks {:?}", cyclic),
+        }]));
+    }
+
+    let mut slots: Vec<Option<TaskContract>> = contracts.into_iter().map(Some).collect();
+    Ok(order.into_iter().filter_map(|i| slots[i].take()).collect())
+}
+
+struct BatchEntry {
+    batch: ContractBatch,
+    cancel: watch::Sender<bool>,
+}
+
+/// Runs batches of task contracts against the backend
+#[derive(Clone)]
+pub struct ContractDispatcher {
+    app_handle: tauri::AppHandle,
+    http: reqwest::Client,
+    batches: Arc<RwLock<HashMap<String, BatchEntry>>>,
+}
+
+impl ContractDispatcher {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self {
+            app_handle,
+            http: reqwest::Client::new(),
+            batches: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Validate a batch, then run it in the background; returns the batch id
+    pub async fn dispatch(
+        &self,
+        backend_base_url: &str,
+        contracts: Vec<Value>,
+        max_concurrency: Option<usize>,
+    ) -> Result<String, IpcError> {
+        if contracts.is_empty() {
+            return Err("No task contracts to dispatch".to_string().into());
+        }
+        let contracts = order_contracts(parse_contracts(contracts)?)?;
+
+        let max_concurrency = max_concurrency
+            .or_else(|| BudgetConfig::load().ok().and_then(|b| b.max_concurrency))
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1);
+
+        let created_at = now_secs();
+        let batch = ContractBatch {
+            id: Uuid::new_v4().to_string(),
+            created_at,
+            finished_at: None,
+            max_concurrency,
+            tasks: contracts
+                .iter()
+                .map(|c| TaskRecord {
+                    task_id: c.task_id.clone(),
+                    assigned_agent: c.assigned_agent,
+                    dependencies: c.dependencies.clone(),
+                    state: TaskState::Pending,
+                    deadline_at: c.deadline.map(|d| created_at + d),
+                    started_at: None,
+                    finished_at: None,
+                    output: None,
+                    error: None,
+                })
+                .collect(),
+        };
+        let batch_id = batch.id.clone();
+
+        let (cancel, cancel_rx) = watch::channel(false);
+        {
+            let mut batches = self.batches.write().await;
+            Self::prune(&mut batches);
+            batches.insert(batch_id.clone(), BatchEntry { batch, cancel });
+        }
+
+        let dispatcher = self.clone();
+        let backend_base_url = backend_base_url.to_string();
+        let id = batch_id.clone();
+        tauri::async_runtime::spawn(async move {
+            dispatcher
+                .run(
+                    &id,
+                    &backend_base_url,
+                    contracts,
+                    max_concurrency,
+                    cancel_rx,
+                )
+                .await;
+        });
+
+        Ok(batch_id)
+    }
+
+    fn prune(batches: &mut HashMap<String, BatchEntry>) {
+        let mut finished: Vec<(String, f64)> = batches
+            .values()
+            .filter_map(|e| e.batch.finished_at.map(|t| (e.batch.id.clone(), t)))
+            .collect();
+        if finished.len() < MAX_FINISHED_BATCHES {
+            return;
+        }
+        finished.sort_by(|a, b| a.1.total_cmp(&b.1));
+        for (id, _) in finished
+            .iter()
+            .take(finished.len() + 1 - MAX_FINISHED_BATCHES)
+        {
+            batches.remove(id);
+        }
+    }
+
+    async fn run(
+        &self,
+        batch_id: &str,
+        backend_base_url: &str,
+        contracts: Vec<TaskContract>,
+        max_concurrency: usize,
+        mut cancel: watch::Receiver<bool>,
+    ) {
+        let contracts: HashMap<String, TaskContract> = contracts
+            .into_iter()
+            .map(|c| (c.task_id.clone(), c))
+            .collect();
+        let mut running: JoinSet<(String, TaskState, Option<String>, Option<String>)> =
+            JoinSet::new();
+        // Lets a panicked task, which yields only a JoinError, be mapped back to
+        // its id (debug builds only; release builds abort on panic)
+        let mut running_ids: HashMap<task::Id, String> = HashMap::new();
+
+        loop {
+            let Some(tasks) = self.tasks(batch_id).await else {
+                return;
+            };
+            let states: HashMap<&str, TaskState> = tasks
+                .iter()
+                .map(|t| (t.task_id.as_str(), t.state))
+                .collect();
+
+            // Settle tasks that can no longer run, then start ready ones
+            let mut in_flight = running.len();
+            let mut next_deadline: Option<f64> = None;
+            for task in tasks.iter().filter(|t| t.state == TaskState::Pending) {
+                let dependency_states: Vec<TaskState> = task
+                    .dependencies
+                    .iter()
+                    .map(|d| states[d.as_str()])
+                    .collect();
+
+                if dependency_states
+                    .iter()
+                    .any(|s| s.is_finished() && *s != TaskState::Succeeded)
+                {
+                    self.finish_task(
+                        batch_id,
+                        &task.task_id,
+                        TaskState::Skipped,
+                        None,
+                        Some("A dependency did not succeed".to_string()),
+                    )
+                    .await;
+                    continue;
+                }
+                if task.deadline_at.is_some_and(|d| d <= now_secs()) {
+                    self.finish_task(
+                        batch_id,
+                        &task.task_id,
+                        TaskState::TimedOut,
+                        None,
+                        Some("Deadline passed before the task could start".to_string()),
+                    )
+                    .await;
+                    continue;
+                }
+                if in_flight >= max_concurrency
+                    || !dependency_states.iter().all(|s| *s == TaskState::Succeeded)
+                {
+                    if let Some(deadline) = task.deadline_at {
+                        next_deadline = Some(next_deadline.map_or(deadline, |d| d.min(deadline)));
+                    }
+                    continue;
+                }
+
+                let dependency_outputs: Vec<(String, String)> = task
+                    .dependencies
+                    .iter()
+                    .filter_map(|d| {
+                        let output = tasks.iter().find(|t| &t.task_id == d)?.output.clone()?;
+                        Some((d.clone(), output))
+                    })
+                    .collect();
+                let message = task_message(&contracts[&task.task_id], &dependency_outputs);
+                let timeout = task
+                    .deadline_at
+                    .map(|d| Duration::from_secs_f64((d - now_secs()).max(0.0)))
+                    .unwrap_or(Duration::from_secs(TASK_TIMEOUT_SECS));
+
+                self.start_task(batch_id, &task.task_id).await;
+                in_flight += 1;
+
+                let http = self.http.clone();
+                let url = format!("{}/orchestrator/chat", backend_base_url);
+                let task_id = task.task_id.clone();
+                let handle = running.spawn(async move {
+                    match tokio::time::timeout(timeout, send_task(&http, &url, &message)).await {
+                        Ok(Ok(output)) => (task_id, TaskState::Succeeded, Some(output), None),
+                        Ok(Err(e)) => (task_id, TaskState::Failed, None, Some(e)),
+                        Err(_) => (
+                            task_id,
+                            TaskState::TimedOut,
+                            None,
+                            Some("Deadline passed while the task was running".to_string()),
+                        ),
+                    }
+                });
+                running_ids.insert(handle.id(), task.task_id.clone());
+            }
+
+            if running.is_empty() {
+                // Nothing in flight: either all done, or a pass just settled tasks
+                let unfinished = match self.tasks(batch_id).await {
+                    Some(tasks) => tasks.iter().any(|t| !t.state.is_finished()),
+                    None => false,
+                };
+                if !unfinished {
+                    break;
+                }
+                continue;
+            }
+
+            // Wake when a waiting task's deadline passes, so it is marked
+            // TimedOut even if no running task finishes first
+            let deadline_passed = async {
+                match next_deadline {
+                    Some(deadline) => {
+                        let wait = Duration::from_secs_f64((deadline - now_secs()).max(0.0));
+                        tokio::time::sleep_until(Instant::now() + wait).await
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = cancel.changed() => {
+                    running.abort_all();
+                    self.cancel_unfinished(batch_id).await;
+                    break;
+                }
+                joined = running.join_next_with_id() => match joined {
+                    Some(Ok((id, (task_id, state, output, error)))) => {
+                        running_ids.remove(&id);
+                        self.finish_task(batch_id, &task_id, state, output, error).await;
+                    }
+                    Some(Err(join_error)) => {
+                        if let Some(task_id) = running_ids.remove(&join_error.id()) {
+                            let error = format!("Task panicked: {}", panic_message(join_error));
+                            self.finish_task(batch_id, &task_id, TaskState::Failed, None, Some(error))
+                                .await;
+                        }
+                    }
+                    None => {}
+                },
+                _ = deadline_passed => {}
+            }
+        }
+
+        if let Some(entry) = self.batches.write().await.get_mut(batch_id) {
+            entry.batch.finished_at = Some(now_secs());
+        }
+        self.app_handle
+            .emit_all("unity:contract_batch_finished", batch_id)
+            .ok();
+    }
+
+    async fn tasks(&self, batch_id: &str) -> Option<Vec<TaskRecord>> {
+        self.batches
+            .read()
+            .await
+            .get(batch_id)
+            .map(|e| e.batch.tasks.clone())
+    }
+
+    async fn update_task(
+        &self,
+        batch_id: &str,
+        task_id: &str,
+        update: impl FnOnce(&mut TaskRecord),
+    ) {
+        let mut batches = self.batches.write().await;
+        let Some(task) = batches
+            .get_mut(batch_id)
+            .and_then(|e| e.batch.tasks.iter_mut().find(|t| t.task_id == task_id))
+        else {
+            return;
+        };
+
+        update(task);
+        let event = TaskEvent { batch_id, task };
+        self.app_handle.emit_all("unity:contract_task", event).ok();
+    }
+
+    async fn start_task(&self, batch_id: &str, task_id: &str) {
+        self.update_task(batch_id, task_id, |task| {
+            task.state = TaskState::Running;
+            task.started_at = Some(now_secs());
+        })
+        .await;
+    }
+
+    async fn finish_task(
+        &self,
+        batch_id: &str,
+        task_id: &str,
+        state: TaskState,
+        output: Option<String>,
+        error: Option<String>,
+    ) {
+        self.update_task(batch_id, task_id, |task| {
+            task.state = state;
+            task.finished_at = Some(now_secs());
+            task.output = output;
+            task.error = error;
+        })
+        .await;
+    }
+
+    async fn cancel_unfinished(&self, batch_id: &str) {
+        let unfinished: Vec<String> = self
+            .tasks(batch_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|t| !t.state.is_finished())
+            .map(|t| t.task_id)
+            .collect();
+        for task_id in unfinished {
+            self.finish_task(batch_id, &task_id, TaskState::Cancelled, None, None)
+                .await;
+        }
+    }
+
+    pub async fn get(&self, batch_id: &str) -> Option<ContractBatch> {
+        self.batches
+            .read()
+            .await
+            .get(batch_id)
+            .map(|e| e.batch.clone())
+    }
+
+    pub async fn list(&self) -> Vec<ContractBatch> {
+        let mut batches: Vec<ContractBatch> = self
+            .batches
+            .read()
+            .await
+            .values()
+            .map(|e| e.batch.clone())
+            .collect();
+        batches.sort_by(|a, b| b.created_at.total_cmp(&a.created_at));
+        batches
+    }
+
+    pub async fn cancel(&self, batch_id: &str) -> Result<(), String> {
+        let batches = self.batches.read().await;
+        let entry = batches
+            .get(batch_id)
+            .ok_or_else(|| format!("Contract batch not found: {}", batch_id))?;
+        if entry.batch.finished_at.is_some() {
+            return Err(format!("Contract batch already finished: {}", batch_id));
+        }
+        entry.cancel.send_replace(true);
+        Ok(())
+    }
+}
+
+/// Prompt handed to the orchestrator for one contract
+fn task_message(contract: &TaskContract, dependency_outputs: &[(String, String)]) -> String {
+    let agent = serde_json::to_value(contract.assigned_agent)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let mut message = format!(
+        "[{} agent] Task {}: {}",
+        agent, contract.task_id, contract.description
+    );
+
+    if !dependency_outputs.is_empty() {
+        message.push_str("\n\nResults of prerequisite tasks:");
+        for (task_id, output) in dependency_outputs {
+            message.push_str(&format!("\n- {}: {}", task_id, output));
+        }
+    }
+    if let Some(schema) = &contract.output_schema {
+        message.push_str(&format!(
+            "\n\nRespond with JSON matching this schema: {}",
+            Value::Object(schema.clone())
+        ));
+    }
+    message
+}
+
+async fn send_task(http: &reqwest::Client, url: &str, message: &str) -> Result<String, String> {
+    let response = http
+        .post(url)
+        .json(&json!({ "message": message, "stream": false }))
+        .send()
+        .await
+        .map_err(|e| format!("Backend not reachable: {}", e))?;
+
+    let status = response.status();
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse backend response: {}", e))?;
+    if !status.is_success() {
+        let error = body["error"].as_str().unwrap_or("unknown error");
+        return Err(format!("Backend error {}: {}", status, error));
+    }
+
+    body["response"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Backend response has no `response` field".to_string())
+}
+
+/// Tauri commands for task contract batches
+#[tauri::command]
+pub async fn dispatch_task_contracts(
+    state: tauri::State<'_, AppState>,
+    dispatcher: tauri::State<'_, ContractDispatcher>,
+    contracts: Vec<Value>,
+    max_concurrency: Option<usize>,
+) -> Result<String, IpcError> {
+    dispatcher
+        .dispatch(&state.backend_base_url, contracts, max_concurrency)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_contract_batch(
+    dispatcher: tauri::State<'_, ContractDispatcher>,
+    batch_id: String,
+) -> Result<ContractBatch, String> {
+    dispatcher
+        .get(&batch_id)
+        .await
+        .ok_or_else(|| format!("Contract batch not found: {}", batch_id))
+}
+
+#[tauri::command]
+pub async fn list_contract_batches(
+    dispatcher: tauri::State<'_, ContractDispatcher>,
+) -> Result<Vec<ContractBatch>, String> {
+    Ok(dispatcher.list().await)
+}
+
+#[tauri::command]
+pub async fn cancel_contract_batch(
+    dispatcher: tauri::State<'_, ContractDispatcher>,
+    batch_id: String,
+) -> Result<(), String> {
+    dispatcher.cancel(&batch_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(task_id: &str, dependencies: &[&str]) -> TaskContract {
+        TaskContract {
+            task_id: task_id.to_string(),
+            description: format!("Carry out {}", task_id),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            assigned_agent: AssignedAgent::Code,
+            deadline: None,
+            output_schema: None,
+        }
+    }
+
+    fn fields(result: Result<Vec<TaskContract>, SchemaViolation>) -> Vec<(String, String)> {
+        result
+            .unwrap_err()
+            .errors
+            .into_iter()
+            .map(|e| (e.field, e.message))
+            .collect()
+    }
+
+    #[test]
+    fn orders_contracts_after_their_dependencies() {
+        let ordered = order_contracts(vec![
+            contract("report", &["code", "research"]),
+            contract("code", &["research"]),
+            contract("research", &[]),
+        ])
+        .unwrap();
+        let ids: Vec<&str> = ordered.iter().map(|c| c.task_id.as_str()).collect();
+
+        assert_eq!(ids, ["research", "code", "report"]);
+    }
+
+    #[test]
+    fn reports_duplicate_ids_self_and_unknown_dependencies() {
+        let errors = fields(order_contracts(vec![
+            contract("plan", &[]),
+            contract("plan", &[]),
+            contract("code", &["code", "review"]),
+        ]));
+
+        assert_eq!(
+            errors,
+            [
+                (
+                    "[1].task_id".to_string(),
+                    "duplicate task id \"plan\"".to_string()
+                ),
+                (
+                    "[2].dependencies[0]".to_string(),
+                    "task depends on itself (\"code\")".to_string()
+                ),
+                (
+                    "[2].dependencies[1]".to_string(),
+                    "unknown task id (\"review\")".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_the_tasks_on_a_dependency_cycle() {
+        let errors = fields(order_contracts(vec![
+            contract("research", &[]),
+            contract("code", &["test", "research"]),
+            contract("test", &["code"]),
+        ]));
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "dependencies");
+        assert_eq!(
+            errors[0].1,
+            "dependency cycle among tasks [\"code\", \"test\"]"
+        );
+    }
+}
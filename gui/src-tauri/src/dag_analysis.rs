@@ -0,0 +1,407 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::WorkflowDAG;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DanglingEdge {
+    /// Index of the edge in `WorkflowDAG.edges`
+    pub index: usize,
+    pub from: String,
+    pub to: String,
+    pub missing_from: bool,
+    pub missing_to: bool,
+}
+
+/// Structural diagnostics for a workflow, for the editor to highlight
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DagAnalysis {
+    /// No dangling edges, duplicate ids or cycles
+    pub valid: bool,
+    pub duplicate_node_ids: Vec<String>,
+    pub dangling_edges: Vec<DanglingEdge>,
+    /// Each entry is one strongly connected group of nodes (or a self-loop)
+    pub cycles: Vec<Vec<String>>,
+    /// Nodes without incoming edges, where execution starts
+    pub entry_nodes: Vec<String>,
+    pub exit_nodes: Vec<String>,
+    /// Nodes no entry node leads to
+    pub unreachable_nodes: Vec<String>,
+    /// Nodes with no edges at all (only reported in multi-node workflows)
+    pub isolated_nodes: Vec<String>,
+    /// Empty when the graph has cycles
+    pub topological_order: Vec<String>,
+    /// Longest entry-to-exit chain of nodes; empty when the graph has cycles
+    pub critical_path: Vec<String>,
+}
+
+/// A workflow together with its diagnostics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyzedWorkflowDAG {
+    #[serde(flatten)]
+    pub dag: WorkflowDAG,
+    pub analysis: DagAnalysis,
+}
+
+/// Adjacency of a workflow over node indices, ignoring dangling edges
+pub(crate) struct Graph<'a> {
+    pub ids: Vec<&'a str>,
+    pub successors: Vec<Vec<usize>>,
+    pub predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> Graph<'a> {
+    /// The first node wins when ids are duplicated
+    pub fn new(dag: &'a WorkflowDAG) -> Self {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for (i, node) in dag.nodes.iter().enumerate() {
+            index.entry(node.id.as_str()).or_insert(i);
+        }
+
+        let n = dag.nodes.len();
+        let mut successors = vec![Vec::new(); n];
+        let mut predecessors = vec![Vec::new(); n];
+        for edge in &dag.edges {
+            if let (Some(&from), Some(&to)) =
+                (index.get(edge.from.as_str()), index.get(edge.to.as_str()))
+            {
+                if !successors[from].contains(&to) {
+                    successors[from].push(to);
+                    predecessors[to].push(from);
+                }
+            }
+        }
+
+        Self {
+            ids: dag.nodes.iter().map(|n| n.id.as_str()).collect(),
+            successors,
+            predecessors,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Kahn's algorithm, taking ready nodes in input order; `None` on a cycle
+    pub fn topological_order(&self) -> Option<Vec<usize>> {
+        let mut remaining: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..self.len()).filter(|i| remaining[*i] == 0).collect();
+        ready.reverse();
+
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(i) = ready.pop() {
+            order.push(i);
+            let mut unlocked = Vec::new();
+            for &next in &self.successors[i] {
+                remaining[next] -= 1;
+                if remaining[next] == 0 {
+                    unlocked.push(next);
+                }
+            }
+            unlocked.sort_unstable_by(|a, b| b.cmp(a));
+            ready.extend(unlocked);
+        }
+
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Strongly connected components that form cycles (Tarjan, iterative so
+    /// long chains cannot overflow the stack)
+    fn cycles(&self) -> Vec<Vec<usize>> {
+        let n = self.len();
+        let mut next_index = 0;
+        let mut index: Vec<Option<usize>> = vec![None; n];
+        let mut low = vec![0; n];
+        let mut stack = Vec::new();
+        let mut on_stack = vec![false; n];
+        let mut components = Vec::new();
+
+        for root in 0..n {
+            if index[root].is_some() {
+                continue;
+            }
+            // Call stack of (vertex, next successor to look at)
+            let mut calls = vec![(root, 0usize)];
+            while let Some((v, next)) = calls.last_mut() {
+                let v = *v;
+                if *next == 0 {
+                    index[v] = Some(next_index);
+                    low[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                }
+
+                if let Some(&w) = self.successors[v].get(*next) {
+                    *next += 1;
+                    match index[w] {
+                        None => calls.push((w, 0)),
+                        Some(i) if on_stack[w] => low[v] = low[v].min(i),
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                calls.pop();
+                if let Some(&(parent, _)) = calls.last() {
+                    low[parent] = low[parent].min(low[v]);
+                }
+                if Some(low[v]) == index[v] {
+                    let mut component = Vec::new();
+                    while let Some(w) = stack.pop() {
+                        on_stack[w] = false;
+                        component.push(w);
+                        if w == v {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+
+        let mut cycles: Vec<Vec<usize>> = components
+            .into_iter()
+            .filter(|c| c.len() > 1 || self.successors[c[0]].contains(&c[0]))
+            .collect();
+        cycles.sort();
+        cycles
+    }
+
+    fn reachable_from(&self, starts: &[usize]) -> Vec<bool> {
+        let mut seen = vec![false; self.len()];
+        let mut stack = starts.to_vec();
+        while let Some(v) = stack.pop() {
+            if !std::mem::replace(&mut seen[v], true) {
+                stack.extend(&self.successors[v]);
+            }
+        }
+        seen
+    }
+
+    /// Longest chain of nodes, given a topological order
+    fn critical_path(&self, order: &[usize]) -> Vec<usize> {
+        let mut length = vec![1usize; self.len()];
+        let mut previous: Vec<Option<usize>> = vec![None; self.len()];
+        for &v in order {
+            for &w in &self.successors[v] {
+                if length[v] + 1 > length[w] {
+                    length[w] = length[v] + 1;
+                    previous[w] = Some(v);
+                }
+            }
+        }
+
+        // Ties go to the node earliest in topological order
+        let Some(mut end) =
+            order
+                .iter()
+                .copied()
+                .reduce(|best, v| if length[v] > length[best] { v } else { best })
+        else {
+            return Vec::new();
+        };
+
+        let mut path = vec![end];
+        while let Some(v) = previous[end] {
+            path.push(v);
+            end = v;
+        }
+        path.reverse();
+        path
+    }
+}
+
+pub fn analyze(dag: &WorkflowDAG) -> DagAnalysis {
+    let graph = Graph::new(dag);
+    let names = |indices: &[usize]| -> Vec<String> {
+        indices.iter().map(|&i| graph.ids[i].to_string()).collect()
+    };
+
+    let mut seen = HashSet::new();
+    let mut duplicate_node_ids: Vec<String> = Vec::new();
+    for node in &dag.nodes {
+        if !seen.insert(node.id.as_str()) && !duplicate_node_ids.contains(&node.id) {
+            duplicate_node_ids.push(node.id.clone());
+        }
+    }
+
+    let dangling_edges: Vec<DanglingEdge> = dag
+        .edges
+        .iter()
+        .enumerate()
+        .filter_map(|(index, edge)| {
+            let missing_from = !seen.contains(edge.from.as_str());
+            let missing_to = !seen.contains(edge.to.as_str());
+            (missing_from || missing_to).then(|| DanglingEdge {
+                index,
+                from: edge.from.clone(),
+                to: edge.to.clone(),
+                missing_from,
+                missing_to,
+            })
+        })
+        .collect();
+
+    let cycles: Vec<Vec<String>> = graph.cycles().iter().map(|c| names(c)).collect();
+
+    // Duplicates are analysed through their first occurrence only
+    let mut first_seen = HashSet::new();
+    let unique: Vec<usize> = (0..graph.len())
+        .filter(|&i| first_seen.insert(graph.ids[i]))
+        .collect();
+    let entries: Vec<usize> = unique
+        .iter()
+        .copied()
+        .filter(|&i| graph.predecessors[i].is_empty())
+        .collect();
+    let exits: Vec<usize> = unique
+        .iter()
+        .copied()
+        .filter(|&i| graph.successors[i].is_empty())
+        .collect();
+    let reachable = graph.reachable_from(&entries);
+    let unreachable: Vec<usize> = unique.iter().copied().filter(|&i| !reachable[i]).collect();
+    let isolated: Vec<usize> = if unique.len() > 1 {
+        unique
+            .iter()
+            .copied()
+            .filter(|&i| graph.predecessors[i].is_empty() && graph.successors[i].is_empty())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let order: Vec<usize> = graph
+        .topological_order()
+        .map(|order| {
+            order
+                .into_iter()
+                .filter(|&i| unique.binary_search(&i).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let critical_path = graph.critical_path(&order);
+
+    DagAnalysis {
+        valid: duplicate_node_ids.is_empty() && dangling_edges.is_empty() && cycles.is_empty(),
+        duplicate_node_ids,
+        dangling_edges,
+        cycles,
+        entry_nodes: names(&entries),
+        exit_nodes: names(&exits),
+        unreachable_nodes: names(&unreachable),
+        isolated_nodes: names(&isolated),
+        topological_order: names(&order),
+        critical_path: names(&critical_path),
+    }
+}
+
+impl From<WorkflowDAG> for AnalyzedWorkflowDAG {
+    fn from(dag: WorkflowDAG) -> Self {
+        let analysis = analyze(&dag);
+        Self { dag, analysis }
+    }
+}
+
+/// Tauri command for analysing a workflow edited in the GUI
+#[tauri::command]
+pub async fn analyze_workflow_dag(dag: WorkflowDAG) -> Result<DagAnalysis, String> {
+    Ok(analyze(&dag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_each_strongly_connected_group_and_self_loop() {
+        let dag = WorkflowDAG::from_edges(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("b", "c"), ("c", "a"), ("c", "d"), ("e", "e")],
+        );
+        let analysis = analyze(&dag);
+
+        assert!(!analysis.valid);
+        assert_eq!(analysis.cycles, vec![vec!["a", "b", "c"], vec!["e"]]);
+        assert!(analysis.topological_order.is_empty());
+        assert!(analysis.critical_path.is_empty());
+    }
+
+    #[test]
+    fn long_chains_do_not_overflow_the_cycle_search() {
+        let ids: Vec<String> = (0..200_000).map(|i| format!("n{}", i)).collect();
+        let ids: Vec<&str> = ids.iter().map(String::as_str).collect();
+        let mut edges: Vec<(&str, &str)> = ids.windows(2).map(|w| (w[0], w[1])).collect();
+        edges.push((ids[ids.len() - 1], ids[0]));
+        let dag = WorkflowDAG::from_edges(&ids, &edges);
+
+        let cycles = Graph::new(&dag).cycles();
+
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].len(), ids.len());
+    }
+
+    #[test]
+    fn orders_ready_nodes_by_input_position() {
+        let dag = WorkflowDAG::from_edges(
+            &["plan", "research", "code", "review"],
+            &[
+                ("plan", "code"),
+                ("plan", "research"),
+                ("research", "review"),
+                ("code", "review"),
+            ],
+        );
+        let analysis = analyze(&dag);
+
+        assert!(analysis.valid);
+        assert_eq!(
+            analysis.topological_order,
+            vec!["plan", "research", "code", "review"]
+        );
+        assert_eq!(analysis.entry_nodes, vec!["plan"]);
+        assert_eq!(analysis.exit_nodes, vec!["review"]);
+    }
+
+    #[test]
+    fn critical_path_is_the_longest_chain() {
+        let dag = WorkflowDAG::from_edges(
+            &["a", "b", "c", "d", "e"],
+            &[("a", "b"), ("b", "c"), ("c", "e"), ("a", "d"), ("d", "e")],
+        );
+        assert_eq!(analyze(&dag).critical_path, vec!["a", "b", "c", "e"]);
+    }
+
+    #[test]
+    fn flags_dangling_edges_and_duplicate_ids() {
+        let dag = WorkflowDAG::from_edges(
+            &["a", "b", "a", "lonely"],
+            &[("a", "b"), ("b", "ghost"), ("phantom", "a")],
+        );
+        let analysis = analyze(&dag);
+
+        assert!(!analysis.valid);
+        assert_eq!(analysis.duplicate_node_ids, vec!["a"]);
+        let dangling: Vec<(usize, bool, bool)> = analysis
+            .dangling_edges
+            .iter()
+            .map(|e| (e.index, e.missing_from, e.missing_to))
+            .collect();
+        assert_eq!(dangling, vec![(1, false, true), (2, true, false)]);
+        assert_eq!(analysis.isolated_nodes, vec!["lonely"]);
+        // The duplicate is analysed once, through its first occurrence
+        assert_eq!(analysis.topological_order, vec!["a", "b", "lonely"]);
+    }
+
+    #[test]
+    fn nodes_only_reachable_from_a_cycle_are_unreachable() {
+        let dag = WorkflowDAG::from_edges(
+            &["start", "x", "y", "z"],
+            &[("x", "y"), ("y", "x"), ("y", "z")],
+        );
+        assert_eq!(analyze(&dag).unreachable_nodes, vec!["x", "y", "z"]);
+    }
+}
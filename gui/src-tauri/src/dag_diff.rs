@@ -0,0 +1,392 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::{WorkflowDAG, WorkflowEdge, WorkflowNode};
+
+/// Moves smaller than this (in canvas units) are not reported
+const POSITION_TOLERANCE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    Id,
+    /// The id changed but the label (and preferably the type) did not
+    Label,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeChange {
+    pub before_id: String,
+    pub after_id: String,
+    pub matched_by: MatchedBy,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EdgeChange {
+    /// Endpoints in terms of the new node ids
+    pub from: String,
+    pub to: String,
+    pub before_label: Option<String>,
+    pub after_label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Modified,
+    Unchanged,
+}
+
+/// Structural difference between two workflow versions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DagDiff {
+    pub added_nodes: Vec<WorkflowNode>,
+    pub removed_nodes: Vec<WorkflowNode>,
+    pub modified_nodes: Vec<NodeChange>,
+    pub unchanged_nodes: usize,
+    pub added_edges: Vec<WorkflowEdge>,
+    /// Removed edges keep their old endpoint ids
+    pub removed_edges: Vec<WorkflowEdge>,
+    pub modified_edges: Vec<EdgeChange>,
+    pub unchanged_edges: usize,
+    /// Overlay status per node id; removed nodes use their old id
+    pub node_status: HashMap<String, DiffStatus>,
+    /// Nothing was added, removed or modified
+    pub identical: bool,
+}
+
+impl DagDiff {
+    fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.modified_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.modified_edges.is_empty()
+    }
+}
+
+/// Pair before/after nodes by id, then leftover nodes by label. A label match
+/// prefers a node of the same type.
+fn match_nodes(before: &[WorkflowNode], after: &[WorkflowNode]) -> Vec<(usize, usize, MatchedBy)> {
+    let mut before_taken = vec![false; before.len()];
+    let mut after_taken = vec![false; after.len()];
+    let mut pairs = Vec::new();
+
+    let mut by_id: HashMap<&str, usize> = HashMap::new();
+    for (i, node) in before.iter().enumerate() {
+        by_id.entry(node.id.as_str()).or_insert(i);
+    }
+    for (j, node) in after.iter().enumerate() {
+        if let Some(&i) = by_id.get(node.id.as_str()) {
+            if !before_taken[i] {
+                before_taken[i] = true;
+                after_taken[j] = true;
+                pairs.push((i, j, MatchedBy::Id));
+            }
+        }
+    }
+
+    for (j, node) in after.iter().enumerate() {
+        if after_taken[j] {
+            continue;
+        }
+        let candidates =
+            || (0..before.len()).filter(|&i| !before_taken[i] && before[i].label == node.label);
+        let found = candidates()
+            .find(|&i| before[i].node_type == node.node_type)
+            .or_else(|| candidates().next());
+        if let Some(i) = found {
+            before_taken[i] = true;
+            after_taken[j] = true;
+            pairs.push((i, j, MatchedBy::Label));
+        }
+    }
+
+    pairs.sort_by_key(|&(_, j, _)| j);
+    pairs
+}
+
+fn node_changes(before: &WorkflowNode, after: &WorkflowNode) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if before.id != after.id {
+        changes.push(FieldChange {
+            field: "id".to_string(),
+            before: json!(before.id),
+            after: json!(after.id),
+        });
+    }
+    if before.node_type != after.node_type {
+        changes.push(FieldChange {
+            field: "node_type".to_string(),
+            before: json!(before.node_type),
+            after: json!(after.node_type),
+        });
+    }
+    if before.label != after.label {
+        changes.push(FieldChange {
+            field: "label".to_string(),
+            before: json!(before.label),
+            after: json!(after.label),
+        });
+    }
+    let moved = (before.position.x - after.position.x).abs() > POSITION_TOLERANCE
+        || (before.position.y - after.position.y).abs() > POSITION_TOLERANCE;
+    if moved {
+        changes.push(FieldChange {
+            field: "position".to_string(),
+            before: json!(before.position),
+            after: json!(after.position),
+        });
+    }
+    changes
+}
+
+pub fn diff(before: &WorkflowDAG, after: &WorkflowDAG) -> DagDiff {
+    let mut result = DagDiff::default();
+    let pairs = match_nodes(&before.nodes, &after.nodes);
+
+    let mut before_matched = vec![false; before.nodes.len()];
+    let mut after_matched = vec![false; after.nodes.len()];
+    // Old id -> new id, so edges can be compared across renames
+    let mut renamed: HashMap<&str, &str> = HashMap::new();
+
+    for &(i, j, matched_by) in &pairs {
+        before_matched[i] = true;
+        after_matched[j] = true;
+        let (old, new) = (&before.nodes[i], &after.nodes[j]);
+        renamed.insert(old.id.as_str(), new.id.as_str());
+
+        let changes = node_changes(old, new);
+        if changes.is_empty() {
+            result.unchanged_nodes += 1;
+            result
+                .node_status
+                .insert(new.id.clone(), DiffStatus::Unchanged);
+        } else {
+            result
+                .node_status
+                .insert(new.id.clone(), DiffStatus::Modified);
+            result.modified_nodes.push(NodeChange {
+                before_id: old.id.clone(),
+                after_id: new.id.clone(),
+                matched_by,
+                changes,
+            });
+        }
+    }
+    for (j, node) in after.nodes.iter().enumerate() {
+        if !after_matched[j] {
+            result
+                .node_status
+                .insert(node.id.clone(), DiffStatus::Added);
+            result.added_nodes.push(node.clone());
+        }
+    }
+    for (i, node) in before.nodes.iter().enumerate() {
+        if !before_matched[i] {
+            result
+                .node_status
+                .entry(node.id.clone())
+                .or_insert(DiffStatus::Removed);
+            result.removed_nodes.push(node.clone());
+        }
+    }
+
+    // Edges are a multiset keyed on (from, to) in new ids
+    let mut old_edges: HashMap<(&str, &str), Vec<&WorkflowEdge>> = HashMap::new();
+    for edge in &before.edges {
+        let from = renamed
+            .get(edge.from.as_str())
+            .copied()
+            .unwrap_or(&edge.from);
+        let to = renamed.get(edge.to.as_str()).copied().unwrap_or(&edge.to);
+        old_edges.entry((from, to)).or_default().push(edge);
+    }
+
+    for edge in &after.edges {
+        let Some(candidates) = old_edges.get_mut(&(edge.from.as_str(), edge.to.as_str())) else {
+            result.added_edges.push(edge.clone());
+            continue;
+        };
+        let exact = candidates.iter().position(|old| old.label == edge.label);
+        match exact.or((!candidates.is_empty()).then_some(0)) {
+            None => result.added_edges.push(edge.clone()),
+            Some(k) => {
+                let old = candidates.remove(k);
+                if old.label == edge.label {
+                    result.unchanged_edges += 1;
+                } else {
+                    result.modified_edges.push(EdgeChange {
+                        from: edge.from.clone(),
+                        to: edge.to.clone(),
+                        before_label: old.label.clone(),
+                        after_label: edge.label.clone(),
+                    });
+                }
+            }
+        }
+    }
+    // Report leftovers in their original order
+    for edge in &before.edges {
+        let from = renamed
+            .get(edge.from.as_str())
+            .copied()
+            .unwrap_or(&edge.from);
+        let to = renamed.get(edge.to.as_str()).copied().unwrap_or(&edge.to);
+        if let Some(candidates) = old_edges.get_mut(&(from, to)) {
+            if let Some(k) = candidates.iter().position(|old| std::ptr::eq(*old, edge)) {
+                candidates.remove(k);
+                result.removed_edges.push(edge.clone());
+            }
+        }
+    }
+
+    result.identical = result.is_empty();
+    result
+}
+
+/// Parse a serialized workflow (JSON or YAML) as a DAG
+fn parse_workflow(name: &str, workflow: &str) -> Result<WorkflowDAG, String> {
+    serde_yaml::from_str(workflow)
+        .map_err(|e| format!("{} is not a serialized workflow DAG: {}", name, e))
+}
+
+/// Tauri commands for reviewing workflow changes
+#[tauri::command]
+pub async fn diff_workflow_dags(
+    before: WorkflowDAG,
+    after: WorkflowDAG,
+) -> Result<DagDiff, String> {
+    Ok(diff(&before, &after))
+}
+
+/// Diff a mutation's `workflow` against the `current_workflow` it was made from
+#[tauri::command]
+pub async fn diff_workflow_mutation(
+    current_workflow: String,
+    workflow: String,
+) -> Result<DagDiff, String> {
+    let before = parse_workflow("current_workflow", &current_workflow)?;
+    let after = parse_workflow("workflow", &workflow)?;
+    Ok(diff(&before, &after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labelled(from: &str, to: &str, label: &str) -> WorkflowEdge {
+        WorkflowEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: Some(label.to_string()),
+        }
+    }
+
+    #[test]
+    fn identical_workflows_have_no_changes() {
+        let dag = WorkflowDAG::from_edges(&["a", "b"], &[("a", "b")]);
+        let result = diff(&dag, &dag.clone());
+
+        assert!(result.identical);
+        assert_eq!(result.unchanged_nodes, 2);
+        assert_eq!(result.unchanged_edges, 1);
+    }
+
+    #[test]
+    fn renamed_node_is_matched_by_label_and_keeps_its_edges() {
+        let before = WorkflowDAG::from_edges(&["plan", "code"], &[("plan", "code")]);
+        let mut after = WorkflowDAG::from_edges(&["plan", "code_v2"], &[("plan", "code_v2")]);
+        after.nodes[1].label = "code".to_string();
+
+        let result = diff(&before, &after);
+
+        assert!(result.added_nodes.is_empty());
+        assert!(result.removed_nodes.is_empty());
+        assert_eq!(result.modified_nodes.len(), 1);
+        let change = &result.modified_nodes[0];
+        assert_eq!(
+            (change.before_id.as_str(), change.after_id.as_str()),
+            ("code", "code_v2")
+        );
+        assert_eq!(change.matched_by, MatchedBy::Label);
+        assert_eq!(change.changes.len(), 1);
+        assert_eq!(change.changes[0].field, "id");
+
+        assert_eq!(result.unchanged_edges, 1);
+        assert!(result.added_edges.is_empty() && result.removed_edges.is_empty());
+        assert_eq!(result.node_status["code_v2"], DiffStatus::Modified);
+    }
+
+    #[test]
+    fn label_match_prefers_a_node_of_the_same_type() {
+        let mut before = WorkflowDAG::from_edges(&["x1", "x2"], &[]);
+        before.nodes[0].label = "review".to_string();
+        before.nodes[1].label = "review".to_string();
+        before.nodes[1].node_type = "evaluator".to_string();
+        let mut after = WorkflowDAG::from_edges(&["review_v2"], &[]);
+        after.nodes[0].label = "review".to_string();
+        after.nodes[0].node_type = "evaluator".to_string();
+
+        let result = diff(&before, &after);
+
+        assert_eq!(result.modified_nodes[0].before_id, "x2");
+        assert_eq!(result.removed_nodes[0].id, "x1");
+        assert_eq!(result.node_status["x1"], DiffStatus::Removed);
+    }
+
+    #[test]
+    fn parallel_edges_are_compared_as_a_multiset() {
+        let mut before = WorkflowDAG::from_edges(&["a", "b"], &[]);
+        before.edges = vec![
+            labelled("a", "b", "draft"),
+            labelled("a", "b", "review"),
+            labelled("a", "b", "review"),
+        ];
+        let mut after = before.clone();
+        after.edges = vec![labelled("a", "b", "review"), labelled("a", "b", "final")];
+
+        let result = diff(&before, &after);
+
+        // One `review` edge is kept, `draft` is relabelled `final`, the other `review` is gone
+        assert_eq!(result.unchanged_edges, 1);
+        assert_eq!(result.modified_edges.len(), 1);
+        assert_eq!(
+            result.modified_edges[0].before_label.as_deref(),
+            Some("draft")
+        );
+        assert_eq!(
+            result.modified_edges[0].after_label.as_deref(),
+            Some("final")
+        );
+        assert_eq!(result.removed_edges.len(), 1);
+        assert_eq!(result.removed_edges[0].label.as_deref(), Some("review"));
+        assert!(result.added_edges.is_empty());
+    }
+
+    #[test]
+    fn small_moves_are_ignored_and_new_nodes_are_added() {
+        let before = WorkflowDAG::from_edges(&["a"], &[]);
+        let mut after = WorkflowDAG::from_edges(&["a", "b"], &[("a", "b")]);
+        after.nodes[0].position.x += POSITION_TOLERANCE / 2.0;
+
+        let result = diff(&before, &after);
+
+        assert_eq!(result.unchanged_nodes, 1);
+        assert_eq!(result.added_nodes[0].id, "b");
+        assert_eq!(result.added_edges.len(), 1);
+        assert_eq!(result.node_status["b"], DiffStatus::Added);
+    }
+}
@@ -0,0 +1,367 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use crate::dag_layout;
+use crate::{WorkflowDAG, WorkflowEdge, WorkflowNode};
+
+/// SVG node box, centred on the node position
+const NODE_WIDTH: f64 = 140.0;
+const NODE_HEIGHT: f64 = 48.0;
+
+/// Blank space around the drawing
+const SVG_MARGIN: f64 = 40.0;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Dot,
+    Mermaid,
+    Svg,
+}
+
+/// Fill and stroke colours per `node_type`
+fn node_style(node_type: &str) -> (&'static str, &'static str) {
+    match node_type {
+        "evaluator" => ("#1F3A5F", "#4A90D9"),
+        "mutator" => ("#5C3310", "#E67E22"),
+        "bandit" => ("#3E2450", "#9B59B6"),
+        "memory" => ("#16432A", "#27AE60"),
+        _ => ("#2B2B2B", "#7F8C8D"),
+    }
+}
+
+/// Node types in first-seen order, for one style declaration each
+fn node_types(dag: &WorkflowDAG) -> Vec<&str> {
+    let mut seen = HashSet::new();
+    dag.nodes
+        .iter()
+        .map(|n| n.node_type.as_str())
+        .filter(|t| seen.insert(*t))
+        .collect()
+}
+
+/// Edges whose endpoints both exist; dangling ones are left to `dag_analysis`
+fn drawable_edges(dag: &WorkflowDAG) -> impl Iterator<Item = &WorkflowEdge> {
+    let ids: HashSet<&str> = dag.nodes.iter().map(|n| n.id.as_str()).collect();
+    dag.edges
+        .iter()
+        .filter(move |e| ids.contains(e.from.as_str()) && ids.contains(e.to.as_str()))
+}
+
+fn escape_quoted(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn to_dot(dag: &WorkflowDAG) -> String {
+    let mut out = String::from("digraph workflow {\n");
+    out.push_str("  rankdir=LR;\n");
+    out.push_str("  node [shape=box, style=\"rounded,filled\", fontname=\"Helvetica\", fontcolor=\"#FFFFFF\"];\n");
+    out.push_str("  edge [color=\"#888888\", fontname=\"Helvetica\", fontsize=10];\n");
+
+    for node in &dag.nodes {
+        let (fill, stroke) = node_style(&node.node_type);
+        writeln!(
+            out,
+            "  \"{}\" [label=\"{}\", fillcolor=\"{}\", color=\"{}\", tooltip=\"{}\"];",
+            escape_quoted(&node.id),
+            escape_quoted(&node.label),
+            fill,
+            stroke,
+            escape_quoted(&node.node_type)
+        )
+        .unwrap();
+    }
+    for edge in drawable_edges(dag) {
+        write!(
+            out,
+            "  \"{}\" -> \"{}\"",
+            escape_quoted(&edge.from),
+            escape_quoted(&edge.to)
+        )
+        .unwrap();
+        if let Some(label) = &edge.label {
+            write!(out, " [label=\"{}\"]", escape_quoted(label)).unwrap();
+        }
+        out.push_str(";\n");
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid ids allow only `[A-Za-z0-9_]`, and `end` is a keyword
+fn mermaid_ids(dag: &WorkflowDAG) -> HashMap<&str, String> {
+    let mut ids = HashMap::new();
+    let mut taken = HashSet::new();
+    for (i, node) in dag.nodes.iter().enumerate() {
+        if ids.contains_key(node.id.as_str()) {
+            continue;
+        }
+        let mut id: String = node
+            .id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        if id.is_empty() || id.eq_ignore_ascii_case("end") {
+            id.push('_');
+        }
+        if !taken.insert(id.clone()) {
+            id = format!("{}_{}", id, i);
+            taken.insert(id.clone());
+        }
+        ids.insert(node.id.as_str(), id);
+    }
+    ids
+}
+
+fn escape_mermaid(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn mermaid_class(node_type: &str) -> String {
+    let class: String = node_type
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("type_{}", class)
+}
+
+pub fn to_mermaid(dag: &WorkflowDAG) -> String {
+    let ids = mermaid_ids(dag);
+    let mut out = String::from("flowchart LR\n");
+
+    for node in &dag.nodes {
+        writeln!(
+            out,
+            "    {}[\"{}\"]:::{}",
+            ids[node.id.as_str()],
+            escape_mermaid(&node.label),
+            mermaid_class(&node.node_type)
+        )
+        .unwrap();
+    }
+    for edge in drawable_edges(dag) {
+        let (from, to) = (&ids[edge.from.as_str()], &ids[edge.to.as_str()]);
+        match &edge.label {
+            Some(label) => writeln!(
+                out,
+                "    {} -->|\"{}\"| {}",
+                from,
+                escape_mermaid(label),
+                to
+            )
+            .unwrap(),
+            None => writeln!(out, "    {} --> {}", from, to).unwrap(),
+        }
+    }
+    for node_type in node_types(dag) {
+        let (fill, stroke) = node_style(node_type);
+        writeln!(
+            out,
+            "    classDef {} fill:{},stroke:{},color:#FFFFFF",
+            mermaid_class(node_type),
+            fill,
+            stroke
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Point where the segment from the centre of `node` towards (dx, dy) leaves its box
+fn box_exit(node: &WorkflowNode, dx: f64, dy: f64) -> (f64, f64) {
+    let (hw, hh) = (NODE_WIDTH / 2.0, NODE_HEIGHT / 2.0);
+    let scale = match (dx.abs() > f64::EPSILON, dy.abs() > f64::EPSILON) {
+        (true, true) => (hw / dx.abs()).min(hh / dy.abs()),
+        (true, false) => hw / dx.abs(),
+        (false, true) => hh / dy.abs(),
+        (false, false) => 0.0,
+    };
+    (node.position.x + dx * scale, node.position.y + dy * scale)
+}
+
+/// Standalone SVG drawn at the stored node positions. Workflows whose nodes
+/// overlap (e.g. all at the origin) are laid out first.
+pub fn to_svg(dag: &WorkflowDAG) -> String {
+    let laid_out;
+    let dag = if dag_layout::needs_layout(dag) {
+        laid_out = dag_layout::layout(dag.clone(), &HashSet::new(), &Default::default()).dag;
+        &laid_out
+    } else {
+        dag
+    };
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (0.0f64, 0.0f64, 0.0f64, 0.0f64);
+    for (i, node) in dag.nodes.iter().enumerate() {
+        let (x, y) = (node.position.x, node.position.y);
+        if i == 0 {
+            (min_x, min_y, max_x, max_y) = (x, y, x, y);
+        }
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let left = min_x - NODE_WIDTH / 2.0 - SVG_MARGIN;
+    let top = min_y - NODE_HEIGHT / 2.0 - SVG_MARGIN;
+    let width = max_x - min_x + NODE_WIDTH + 2.0 * SVG_MARGIN;
+    let height = max_y - min_y + NODE_HEIGHT + 2.0 * SVG_MARGIN;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w:.0}\" height=\"{h:.0}\" viewBox=\"{l:.1} {t:.1} {w:.1} {h:.1}\" font-family=\"Helvetica, Arial, sans-serif\">",
+        l = left,
+        t = top,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    out.push_str("  <defs>\n    <marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto-start-reverse\">\n      <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#888888\"/>\n    </marker>\n  </defs>\n");
+    writeln!(
+        out,
+        "  <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#111111\"/>",
+        left, top, width, height
+    )
+    .unwrap();
+
+    let nodes: HashMap<&str, &WorkflowNode> =
+        dag.nodes.iter().rev().map(|n| (n.id.as_str(), n)).collect();
+
+    out.push_str("  <g class=\"edges\">\n");
+    for edge in drawable_edges(dag) {
+        let (from, to) = (nodes[edge.from.as_str()], nodes[edge.to.as_str()]);
+        let dx = to.position.x - from.position.x;
+        let dy = to.position.y - from.position.y;
+        let (x1, y1) = box_exit(from, dx, dy);
+        let (x2, y2) = box_exit(to, -dx, -dy);
+        writeln!(
+            out,
+            "    <line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"#888888\" stroke-width=\"1.5\" marker-end=\"url(#arrow)\"/>",
+            x1, y1, x2, y2
+        )
+        .unwrap();
+        if let Some(label) = &edge.label {
+            writeln!(
+                out,
+                "    <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"11\" fill=\"#BBBBBB\" text-anchor=\"middle\" stroke=\"#111111\" stroke-width=\"3\" paint-order=\"stroke\">{}</text>",
+                (x1 + x2) / 2.0,
+                (y1 + y2) / 2.0 - 4.0,
+                escape_xml(label)
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("  </g>\n");
+
+    out.push_str("  <g class=\"nodes\">\n");
+    for node in &dag.nodes {
+        let (fill, stroke) = node_style(&node.node_type);
+        let (x, y) = (node.position.x, node.position.y);
+        writeln!(
+            out,
+            "    <g class=\"node {}\">\n      <title>{} ({})</title>\n      <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.0}\" height=\"{:.0}\" rx=\"8\" fill=\"{}\" stroke=\"{}\" stroke-width=\"2\"/>\n      <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"13\" fill=\"#FFFFFF\" text-anchor=\"middle\">{}</text>\n      <text x=\"{:.1}\" y=\"{:.1}\" font-size=\"9\" fill=\"{}\" text-anchor=\"middle\">{}</text>\n    </g>",
+            escape_xml(&mermaid_class(&node.node_type)),
+            escape_xml(&node.id),
+            escape_xml(&node.node_type),
+            x - NODE_WIDTH / 2.0,
+            y - NODE_HEIGHT / 2.0,
+            NODE_WIDTH,
+            NODE_HEIGHT,
+            fill,
+            stroke,
+            x,
+            y - 2.0,
+            escape_xml(&node.label),
+            x,
+            y + 14.0,
+            stroke,
+            escape_xml(&node.node_type)
+        )
+        .unwrap();
+    }
+    out.push_str("  </g>\n</svg>\n");
+    out
+}
+
+pub fn export(dag: &WorkflowDAG, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Dot => to_dot(dag),
+        ExportFormat::Mermaid => to_mermaid(dag),
+        ExportFormat::Svg => to_svg(dag),
+    }
+}
+
+/// Tauri command for exporting a workflow as DOT, Mermaid or SVG text
+#[tauri::command]
+pub async fn export_workflow_dag(dag: WorkflowDAG, format: ExportFormat) -> Result<String, String> {
+    Ok(export(&dag, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_escapes_quotes_and_backslashes() {
+        let mut dag = WorkflowDAG::from_edges(&["a", "b"], &[("a", "b")]);
+        dag.nodes[0].label = "say \"hi\" C:\\tmp".to_string();
+        dag.edges[0].label = Some("on \"ok\"".to_string());
+
+        let dot = to_dot(&dag);
+
+        assert!(dot.contains("label=\"say \\\"hi\\\" C:\\\\tmp\""));
+        assert!(dot.contains("\"a\" -> \"b\" [label=\"on \\\"ok\\\"\"];"));
+    }
+
+    #[test]
+    fn mermaid_ids_avoid_keywords_and_non_ascii() {
+        let dag = WorkflowDAG::from_edges(
+            &["end", "café", "caf_", "", "a-b"],
+            &[("end", "café"), ("café", "caf_")],
+        );
+
+        let ids = mermaid_ids(&dag);
+
+        assert_eq!(ids["end"], "end_");
+        assert_eq!(ids["café"], "caf_");
+        assert_eq!(ids["caf_"], "caf__2");
+        assert_eq!(ids[""], "_");
+        assert_eq!(ids["a-b"], "a_b");
+
+        let mermaid = to_mermaid(&dag);
+        assert!(mermaid.contains("    end_ --> caf_\n"));
+        assert!(mermaid.contains("    caf_ --> caf__2\n"));
+    }
+
+    #[test]
+    fn mermaid_labels_replace_quotes() {
+        let mut dag = WorkflowDAG::from_edges(&["a"], &[]);
+        dag.nodes[0].label = "the \"best\" one".to_string();
+
+        assert!(to_mermaid(&dag).contains("a[\"the #quot;best#quot; one\"]:::type_agent"));
+    }
+
+    #[test]
+    fn svg_escapes_markup_in_text() {
+        let mut dag = WorkflowDAG::from_edges(&["a", "b"], &[("a", "b")]);
+        dag.nodes[0].label = "<script>&'\"".to_string();
+        dag.nodes[1].position.x = 300.0;
+        dag.edges[0].label = Some("a < b".to_string());
+
+        let svg = to_svg(&dag);
+
+        assert!(svg.contains("&lt;script&gt;&amp;&apos;&quot;"));
+        assert!(svg.contains(">a &lt; b</text>"));
+        assert!(!svg.contains("<script>"));
+    }
+}
@@ -0,0 +1,423 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::dag_analysis::Graph;
+use crate::{Position, WorkflowDAG};
+
+/// Crossing-reduction sweeps; each alternates direction
+const ORDERING_SWEEPS: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutOptions {
+    /// Distance between layers, along the flow (x)
+    pub layer_spacing: f64,
+    /// Distance between nodes of one layer (y)
+    pub node_spacing: f64,
+    pub origin: Position,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        Self {
+            layer_spacing: 200.0,
+            node_spacing: 100.0,
+            origin: Position { x: 100.0, y: 100.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayoutResult {
+    pub dag: WorkflowDAG,
+    /// Layer of each node, in `dag.nodes` order
+    pub layers: Vec<usize>,
+    /// Edge crossings left after ordering
+    pub crossings: usize,
+}
+
+/// Layered graph with dummy vertices splitting edges that span several layers.
+/// Vertices `0..real` are workflow nodes, the rest are dummies.
+struct Layered {
+    real: usize,
+    layer_of: Vec<usize>,
+    /// Neighbours in the next / previous layer
+    down: Vec<Vec<usize>>,
+    up: Vec<Vec<usize>>,
+    layers: Vec<Vec<usize>>,
+}
+
+/// Edges of `graph` with back edges reversed and self-loops dropped, so the
+/// result is acyclic. Back edges are found by DFS in node order.
+fn acyclic_edges(graph: &Graph) -> Vec<(usize, usize)> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Mark {
+        New,
+        Active,
+        Done,
+    }
+
+    let n = graph.ids.len();
+    let mut mark = vec![Mark::New; n];
+    let mut edges = Vec::new();
+
+    for root in 0..n {
+        if mark[root] != Mark::New {
+            continue;
+        }
+        // Iterative DFS: (vertex, next successor to look at)
+        let mut stack = vec![(root, 0usize)];
+        mark[root] = Mark::Active;
+        while let Some((v, next)) = stack.last_mut() {
+            let v = *v;
+            let Some(&w) = graph.successors[v].get(*next) else {
+                mark[v] = Mark::Done;
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            match mark[w] {
+                _ if w == v => {}
+                Mark::Active => edges.push((w, v)),
+                Mark::Done => edges.push((v, w)),
+                Mark::New => {
+                    edges.push((v, w));
+                    mark[w] = Mark::Active;
+                    stack.push((w, 0));
+                }
+            }
+        }
+    }
+
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+}
+
+/// Longest-path layering, then dummy vertices for long edges
+fn build_layers(n: usize, edges: &[(usize, usize)]) -> Layered {
+    let mut successors = vec![Vec::new(); n];
+    let mut indegree = vec![0usize; n];
+    for &(from, to) in edges {
+        successors[from].push(to);
+        indegree[to] += 1;
+    }
+
+    let mut layer_of = vec![0usize; n];
+    let mut ready: Vec<usize> = (0..n).rev().filter(|&v| indegree[v] == 0).collect();
+    while let Some(v) = ready.pop() {
+        for &w in &successors[v] {
+            layer_of[w] = layer_of[w].max(layer_of[v] + 1);
+            indegree[w] -= 1;
+            if indegree[w] == 0 {
+                ready.push(w);
+            }
+        }
+    }
+
+    let mut down = vec![Vec::new(); n];
+    let mut up = vec![Vec::new(); n];
+    for &(from, to) in edges {
+        let mut previous = from;
+        for layer in layer_of[from] + 1..layer_of[to] {
+            let dummy = layer_of.len();
+            layer_of.push(layer);
+            down.push(Vec::new());
+            up.push(Vec::new());
+            down[previous].push(dummy);
+            up[dummy].push(previous);
+            previous = dummy;
+        }
+        down[previous].push(to);
+        up[to].push(previous);
+    }
+
+    let depth = layer_of.iter().max().map_or(0, |l| l + 1);
+    let mut layers = vec![Vec::new(); depth];
+    for (v, &layer) in layer_of.iter().enumerate() {
+        layers[layer].push(v);
+    }
+
+    Layered {
+        real: n,
+        layer_of,
+        down,
+        up,
+        layers,
+    }
+}
+
+/// Crossings between two adjacent layers, via inversion counting
+fn count_crossings(upper: &[usize], lower_position: &[usize], down: &[Vec<usize>]) -> usize {
+    let mut targets: Vec<usize> = Vec::new();
+    for &v in upper {
+        let mut below: Vec<usize> = down[v].iter().map(|&w| lower_position[w]).collect();
+        below.sort_unstable();
+        targets.extend(below);
+    }
+
+    // Fenwick tree over lower positions
+    let size = targets.iter().max().map_or(0, |m| m + 1);
+    let mut tree = vec![0usize; size + 1];
+    let mut crossings = 0;
+    for (seen, &t) in targets.iter().enumerate() {
+        let mut not_greater = 0;
+        let mut i = t + 1;
+        while i > 0 {
+            not_greater += tree[i];
+            i &= i - 1;
+        }
+        crossings += seen - not_greater;
+
+        let mut i = t + 1;
+        while i <= size {
+            tree[i] += 1;
+            i += i & i.wrapping_neg();
+        }
+    }
+    crossings
+}
+
+impl Layered {
+    fn positions(&self) -> Vec<usize> {
+        let mut position = vec![0; self.layer_of.len()];
+        for layer in &self.layers {
+            for (i, &v) in layer.iter().enumerate() {
+                position[v] = i;
+            }
+        }
+        position
+    }
+
+    fn crossings(&self) -> usize {
+        let position = self.positions();
+        self.layers
+            .windows(2)
+            .map(|pair| count_crossings(&pair[0], &position, &self.down))
+            .sum()
+    }
+
+    /// Reorder one layer by the barycenter of its neighbours in the adjacent layer
+    fn reorder(&mut self, layer: usize, use_up: bool, position: &mut [usize]) {
+        let neighbours = if use_up { &self.up } else { &self.down };
+        let mut keyed: Vec<(f64, usize, usize)> = self.layers[layer]
+            .iter()
+            .map(|&v| {
+                let adjacent = &neighbours[v];
+                let key = if adjacent.is_empty() {
+                    position[v] as f64
+                } else {
+                    adjacent.iter().map(|&w| position[w] as f64).sum::<f64>()
+                        / adjacent.len() as f64
+                };
+                (key, position[v], v)
+            })
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        self.layers[layer] = keyed.into_iter().map(|(_, _, v)| v).collect();
+        for (i, &v) in self.layers[layer].iter().enumerate() {
+            position[v] = i;
+        }
+    }
+
+    /// Alternating barycenter sweeps, keeping the ordering with fewest crossings
+    fn minimize_crossings(&mut self) -> usize {
+        let mut best = self.layers.clone();
+        let mut best_crossings = self.crossings();
+
+        for sweep in 0..ORDERING_SWEEPS {
+            if best_crossings == 0 {
+                break;
+            }
+            let mut position = self.positions();
+            if sweep % 2 == 0 {
+                for layer in 1..self.layers.len() {
+                    self.reorder(layer, true, &mut position);
+                }
+            } else {
+                for layer in (0..self.layers.len().saturating_sub(1)).rev() {
+                    self.reorder(layer, false, &mut position);
+                }
+            }
+
+            let crossings = self.crossings();
+            if crossings < best_crossings {
+                best_crossings = crossings;
+                best = self.layers.clone();
+            }
+        }
+
+        self.layers = best;
+        best_crossings
+    }
+}
+
+/// Lay out `dag` left to right. Nodes listed in `pinned` keep their position;
+/// other nodes placed in the column a pinned node sits in are moved clear of it.
+pub fn layout(
+    mut dag: WorkflowDAG,
+    pinned: &HashSet<String>,
+    options: &LayoutOptions,
+) -> LayoutResult {
+    let graph = Graph::new(&dag);
+    let n = graph.ids.len();
+    let mut layered = build_layers(n, &acyclic_edges(&graph));
+    let crossings = layered.minimize_crossings();
+
+    let widest = layered.layers.iter().map(Vec::len).max().unwrap_or(0);
+    let mut placed: Vec<Option<Position>> = vec![None; n];
+    let pinned_positions: Vec<&Position> = dag
+        .nodes
+        .iter()
+        .filter(|node| pinned.contains(&node.id))
+        .map(|node| &node.position)
+        .collect();
+
+    for (depth, layer) in layered.layers.iter().enumerate() {
+        let x = options.origin.x + depth as f64 * options.layer_spacing;
+        // A pinned node blocks the column it sits in, whatever layer it belongs to
+        let pinned_ys: Vec<f64> = pinned_positions
+            .iter()
+            .filter(|p| (p.x - x).abs() <= options.layer_spacing / 2.0)
+            .map(|p| p.y)
+            .collect();
+
+        // Centre each layer against the widest one
+        let mut cursor =
+            options.origin.y + (widest - layer.len()) as f64 * options.node_spacing / 2.0;
+        for &v in layer {
+            if v < n && pinned.contains(&dag.nodes[v].id) {
+                continue;
+            }
+            let mut y = cursor;
+            while let Some(blocking) = pinned_ys
+                .iter()
+                .find(|&&p| (p - y).abs() < options.node_spacing)
+            {
+                y = blocking + options.node_spacing;
+            }
+            if v < layered.real {
+                placed[v] = Some(Position { x, y });
+            }
+            cursor = y + options.node_spacing;
+        }
+    }
+
+    for (node, position) in dag.nodes.iter_mut().zip(placed) {
+        if let Some(position) = position {
+            node.position = position;
+        }
+    }
+
+    LayoutResult {
+        layers: layered.layer_of[..n].to_vec(),
+        crossings,
+        dag,
+    }
+}
+
+/// True when nodes sit on top of each other, e.g. all at the origin
+pub fn needs_layout(dag: &WorkflowDAG) -> bool {
+    let mut seen = HashSet::new();
+    dag.nodes.iter().any(|node| {
+        let key = (
+            node.position.x.round() as i64,
+            node.position.y.round() as i64,
+        );
+        !seen.insert(key)
+    })
+}
+
+/// Tauri command for re-laying out a workflow in the editor
+#[tauri::command]
+pub async fn layout_workflow_dag(
+    dag: WorkflowDAG,
+    pinned: Option<Vec<String>>,
+    options: Option<LayoutOptions>,
+) -> Result<LayoutResult, String> {
+    let pinned: HashSet<String> = pinned.unwrap_or_default().into_iter().collect();
+    Ok(layout(dag, &pinned, &options.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pinned(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn position(result: &LayoutResult, id: &str) -> (f64, f64) {
+        let node = result.dag.nodes.iter().find(|n| n.id == id).unwrap();
+        (node.position.x, node.position.y)
+    }
+
+    #[test]
+    fn counts_crossings_between_adjacent_layers() {
+        // Upper layer holds vertices 0 and 1; lower layer holds 2 (left) and 3 (right)
+        let lower_position = vec![0, 1, 0, 1];
+        let crossing = vec![vec![3], vec![2], vec![], vec![]];
+        let parallel = vec![vec![2], vec![3], vec![], vec![]];
+        let complete = vec![vec![2, 3], vec![2, 3], vec![], vec![]];
+
+        assert_eq!(count_crossings(&[0, 1], &lower_position, &crossing), 1);
+        assert_eq!(count_crossings(&[0, 1], &lower_position, &parallel), 0);
+        assert_eq!(count_crossings(&[1, 0], &lower_position, &parallel), 1);
+        // Edges sharing an endpoint never cross
+        assert_eq!(count_crossings(&[0, 1], &lower_position, &complete), 1);
+    }
+
+    #[test]
+    fn layers_follow_the_longest_path() {
+        let dag = WorkflowDAG::from_edges(
+            &["a", "b", "c", "d"],
+            &[("a", "b"), ("b", "c"), ("a", "c"), ("c", "d")],
+        );
+        let options = LayoutOptions::default();
+        let result = layout(dag, &HashSet::new(), &options);
+
+        assert_eq!(result.layers, vec![0, 1, 2, 3]);
+        assert_eq!(result.crossings, 0);
+        for (id, layer) in [("a", 0.0), ("b", 1.0), ("c", 2.0), ("d", 3.0)] {
+            let expected = options.origin.x + layer * options.layer_spacing;
+            assert_eq!(position(&result, id).0, expected, "{}", id);
+        }
+    }
+
+    #[test]
+    fn reorders_layers_to_remove_crossings() {
+        let dag = WorkflowDAG::from_edges(&["a", "b", "x", "y"], &[("a", "y"), ("b", "x")]);
+        let result = layout(dag, &HashSet::new(), &LayoutOptions::default());
+
+        assert_eq!(result.crossings, 0);
+        assert!(position(&result, "a").1 < position(&result, "b").1);
+        assert!(position(&result, "y").1 < position(&result, "x").1);
+    }
+
+    #[test]
+    fn pinned_nodes_keep_their_position() {
+        let mut dag = WorkflowDAG::from_edges(&["a", "b"], &[("a", "b")]);
+        dag.nodes[1].position = Position { x: 42.0, y: 420.0 };
+        let result = layout(dag, &pinned(&["b"]), &LayoutOptions::default());
+
+        assert_eq!(position(&result, "b"), (42.0, 420.0));
+    }
+
+    #[test]
+    fn nodes_avoid_a_pin_from_another_layer_in_their_column() {
+        // `c` is in layer 1 but pinned over layer 0's column, where `a` would go
+        let mut dag = WorkflowDAG::from_edges(&["a", "b", "c"], &[("a", "b"), ("a", "c")]);
+        dag.nodes[2].position = Position { x: 110.0, y: 120.0 };
+        let options = LayoutOptions::default();
+        let result = layout(dag, &pinned(&["c"]), &options);
+
+        let (ax, ay) = position(&result, "a");
+        assert_eq!(ax, 100.0);
+        assert!(
+            (ay - 120.0).abs() >= options.node_spacing,
+            "a placed at {}",
+            ay
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+use crate::ollama::OllamaClient;
+use crate::MemorySnapshot;
+
+/// Directory (inside the app data dir) holding cached embedding vectors
+const CACHE_DIR: &str = "embeddings";
+
+/// File (inside `CACHE_DIR`) listing the indexed snapshots; their vectors
+/// are read back from the embedding cache at startup
+const INDEX_FILE: &str = "index.json";
+
+/// Embedding model used when a request does not name one
+pub const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Results returned by a similarity search when `top_k` is not given
+const DEFAULT_TOP_K: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingResult {
+    pub model: String,
+    pub dimensions: usize,
+    pub embeddings: Vec<Vec<f32>>,
+    /// How many of the texts were served from the disk cache
+    pub cached: usize,
+}
+
+/// One cached vector on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedEmbedding {
+    model: String,
+    vector: Vec<f32>,
+}
+
+/// One snapshot in `INDEX_FILE`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    model: String,
+    snapshot: MemorySnapshot,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedSnapshot {
+    snapshot: MemorySnapshot,
+    model: String,
+    vector: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotMatch {
+    pub snapshot: MemorySnapshot,
+    pub score: f32,
+}
+
+/// Key of a (model, text) pair in the disk cache
+fn cache_key(model: &str, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0u8]);
+    hasher.update(text.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+fn snapshot_text(snapshot: &MemorySnapshot) -> String {
+    format!("{}\n{}", snapshot.title, snapshot.note)
+}
+
+/// Ollama embeddings with a disk cache, plus an index of memory snapshots
+/// that is persisted alongside the cache and rebuilt from it at startup
+#[derive(Clone)]
+pub struct EmbeddingService {
+    client: OllamaClient,
+    cache_dir: Option<PathBuf>,
+    index: Arc<RwLock<Vec<IndexedSnapshot>>>,
+}
+
+impl EmbeddingService {
+    pub fn new(app_handle: &tauri::AppHandle, client: OllamaClient) -> Self {
+        let cache_dir = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(CACHE_DIR));
+
+        let mut service = Self {
+            client,
+            cache_dir,
+            index: Arc::new(RwLock::new(Vec::new())),
+        };
+        service.index = Arc::new(RwLock::new(service.load_index()));
+        service
+    }
+
+    fn index_file(&self) -> Option<PathBuf> {
+        self.cache_dir.as_ref().map(|dir| dir.join(INDEX_FILE))
+    }
+
+    /// Rebuild the index from `INDEX_FILE`, skipping snapshots whose vector
+    /// is no longer in the cache
+    fn load_index(&self) -> Vec<IndexedSnapshot> {
+        let Some(raw) = self
+            .index_file()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        else {
+            return Vec::new();
+        };
+        let entries: Vec<IndexEntry> = match serde_json::from_str(&raw) {
+            Ok(entries) => entries,
+            Err(e) => {
+                eprintln!("[Unity] Ignoring unreadable embedding index: {}", e);
+                return Vec::new();
+            }
+        };
+
+        entries
+            .into_iter()
+            .filter_map(|entry| {
+                let vector = self.read_cached(&entry.model, &snapshot_text(&entry.snapshot))?;
+                Some(IndexedSnapshot {
+                    snapshot: entry.snapshot,
+                    model: entry.model,
+                    vector,
+                })
+            })
+            .collect()
+    }
+
+    fn save_index(&self, index: &[IndexedSnapshot]) {
+        let Some(path) = self.index_file() else {
+            return;
+        };
+        let entries: Vec<IndexEntry> = index
+            .iter()
+            .map(|e| IndexEntry {
+                model: e.model.clone(),
+                snapshot: e.snapshot.clone(),
+            })
+            .collect();
+
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| {
+                let raw = serde_json::to_vec(&entries)?;
+                std::fs::write(&path, raw)
+            });
+        if let Err(e) = result {
+            eprintln!("[Unity] Failed to save embedding index: {}", e);
+        }
+    }
+
+    fn cache_file(&self, key: &str) -> Option<PathBuf> {
+        self.cache_dir
+            .as_ref()
+            .map(|dir| dir.join(&key[..2]).join(format!("{}.json", key)))
+    }
+
+    fn read_cached(&self, model: &str, text: &str) -> Option<Vec<f32>> {
+        let path = self.cache_file(&cache_key(model, text))?;
+        let raw = std::fs::read_to_string(path).ok()?;
+        let cached: CachedEmbedding = serde_json::from_str(&raw).ok()?;
+        (cached.model == model).then_some(cached.vector)
+    }
+
+    fn write_cached(&self, model: &str, text: &str, vector: &[f32]) {
+        let Some(path) = self.cache_file(&cache_key(model, text)) else {
+            return;
+        };
+        let cached = CachedEmbedding {
+            model: model.to_string(),
+            vector: vector.to_vec(),
+        };
+
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| {
+                let raw = serde_json::to_vec(&cached)?;
+                std::fs::write(&path, raw)
+            });
+        if let Err(e) = result {
+            eprintln!("[Unity] Failed to cache embedding: {}", e);
+        }
+    }
+
+    /// Embed `texts`, computing only those missing from the cache
+    pub async fn embed(&self, model: &str, texts: &[String]) -> Result<EmbeddingResult, String> {
+        if texts.is_empty() {
+            return Err("No texts to embed".to_string());
+        }
+
+        let mut embeddings: Vec<Option<Vec<f32>>> =
+            texts.iter().map(|t| self.read_cached(model, t)).collect();
+        let cached = embeddings.iter().filter(|e| e.is_some()).count();
+
+        let missing: Vec<usize> = (0..texts.len())
+            .filter(|i| embeddings[*i].is_none())
+            .collect();
+        if !missing.is_empty() {
+            let inputs: Vec<&str> = missing.iter().map(|i| texts[*i].as_str()).collect();
+            let computed = self.request_embeddings(model, &inputs).await?;
+            for (i, vector) in missing.into_iter().zip(computed) {
+                self.write_cached(model, &texts[i], &vector);
+                embeddings[i] = Some(vector);
+            }
+        }
+
+        let embeddings: Vec<Vec<f32>> = embeddings.into_iter().flatten().collect();
+        Ok(EmbeddingResult {
+            model: model.to_string(),
+            dimensions: embeddings.first().map(Vec::len).unwrap_or(0),
+            embeddings,
+            cached,
+        })
+    }
+
+    /// Call `/api/embed`, falling back to the older per-text `/api/embeddings`
+    async fn request_embeddings(
+        &self,
+        model: &str,
+        inputs: &[&str],
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let response = self
+            .client
+            .http()
+            .post(self.client.url("/api/embed"))
+            .json(&json!({ "model": model, "input": inputs }))
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Ollama not reachable: {}", e))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            let mut vectors = Vec::with_capacity(inputs.len());
+            for input in inputs {
+                vectors.push(self.request_legacy_embedding(model, input).await?);
+            }
+            return Ok(vectors);
+        }
+
+        let data = Self::parse_response(response).await?;
+        let vectors: Vec<Vec<f32>> = serde_json::from_value(data["embeddings"].clone())
+            .map_err(|e| format!("Malformed embeddings response: {}", e))?;
+        if vectors.len() != inputs.len() {
+            return Err(format!(
+                "Expected {} embeddings, Ollama returned {}",
+                inputs.len(),
+                vectors.len()
+            ));
+        }
+        Ok(vectors)
+    }
+
+    async fn request_legacy_embedding(&self, model: &str, input: &str) -> Result<Vec<f32>, String> {
+        let response = self
+            .client
+            .http()
+            .post(self.client.url("/api/embeddings"))
+            .json(&json!({ "model": model, "prompt": input }))
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Ollama not reachable: {}", e))?;
+
+        let data = Self::parse_response(response).await?;
+        serde_json::from_value(data["embedding"].clone())
+            .map_err(|e| format!("Malformed embeddings response: {}", e))
+    }
+
+    async fn parse_response(response: reqwest::Response) -> Result<Value, String> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama returned {}: {}", status, detail.trim()));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| format!("Malformed embeddings response: {}", e))
+    }
+
+    /// Add or replace memory snapshots in the similarity index
+    pub async fn index_snapshots(
+        &self,
+        model: &str,
+        snapshots: Vec<MemorySnapshot>,
+    ) -> Result<usize, String> {
+        if snapshots.is_empty() {
+            return Ok(0);
+        }
+
+        let texts: Vec<String> = snapshots.iter().map(snapshot_text).collect();
+        let result = self.embed(model, &texts).await?;
+
+        let mut index = self.index.write().await;
+        for (snapshot, vector) in snapshots.into_iter().zip(result.embeddings) {
+            index.retain(|e| !(e.snapshot.id == snapshot.id && e.model == model));
+            index.push(IndexedSnapshot {
+                snapshot,
+                model: model.to_string(),
+                vector,
+            });
+        }
+        self.save_index(&index);
+        Ok(index.len())
+    }
+
+    /// Snapshots most similar to `query`, best first
+    pub async fn search(
+        &self,
+        model: &str,
+        query: &str,
+        top_k: usize,
+    ) -> Result<Vec<SnapshotMatch>, String> {
+        let result = self.embed(model, &[query.to_string()]).await?;
+        let query_vector = result.embeddings.into_iter().next().unwrap_or_default();
+
+        let index = self.index.read().await;
+        let mut matches: Vec<SnapshotMatch> = index
+            .iter()
+            .filter(|e| e.model == model)
+            .map(|e| SnapshotMatch {
+                snapshot: e.snapshot.clone(),
+                score: cosine_similarity(&query_vector, &e.vector),
+            })
+            .collect();
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+        matches.truncate(top_k);
+        Ok(matches)
+    }
+
+    pub async fn clear_index(&self) {
+        let mut index = self.index.write().await;
+        index.clear();
+        self.save_index(&index);
+    }
+}
+
+/// Tauri commands for embeddings and memory similarity search
+#[tauri::command]
+pub async fn embed_texts(
+    service: tauri::State<'_, EmbeddingService>,
+    texts: Vec<String>,
+    model: Option<String>,
+) -> Result<EmbeddingResult, String> {
+    let model = model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+    service.embed(&model, &texts).await
+}
+
+#[tauri::command]
+pub async fn index_memory_snapshots(
+    service: tauri::State<'_, EmbeddingService>,
+    snapshots: Vec<MemorySnapshot>,
+    model: Option<String>,
+) -> Result<usize, String> {
+    let model = model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+    service.index_snapshots(&model, snapshots).await
+}
+
+#[tauri::command]
+pub async fn search_memory_snapshots(
+    service: tauri::State<'_, EmbeddingService>,
+    query: String,
+    top_k: Option<usize>,
+    model: Option<String>,
+) -> Result<Vec<SnapshotMatch>, String> {
+    let model = model.unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string());
+    service
+        .search(&model, &query, top_k.unwrap_or(DEFAULT_TOP_K))
+        .await
+}
+
+#[tauri::command]
+pub async fn clear_memory_index(service: tauri::State<'_, EmbeddingService>) -> Result<(), String> {
+    service.clear_index().await;
+    Ok(())
+}
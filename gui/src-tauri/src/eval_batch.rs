@@ -0,0 +1,524 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::{watch, RwLock};
+use tokio::task::{self, JoinSet};
+use uuid::Uuid;
+
+use crate::configs::BudgetConfig;
+use crate::eval_cache::EvalCache;
+use crate::evolution_history::EvolutionHistory;
+use crate::util::{mean, now_secs, panic_message};
+use crate::{AppState, EvaluateRequest, EvaluateResponse};
+
+/// Concurrency used when budget.yaml does not set `max_concurrency`
+const DEFAULT_MAX_CONCURRENCY: usize = 2;
+
+/// Batches kept for inspection once finished
+const MAX_FINISHED_BATCHES: usize = 20;
+
+/// One goal/output pair to evaluate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItem {
+    /// Caller's label for the item, e.g. a corpus file name
+    #[serde(default)]
+    pub id: Option<String>,
+    pub goal: String,
+    pub output: String,
+    /// Variant the output came from, for the evolution history
+    #[serde(default)]
+    pub variant_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemResult {
+    pub index: usize,
+    pub id: Option<String>,
+    pub status: ItemStatus,
+    pub result: Option<EvaluateResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+/// Aggregate over the evaluated items
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchReport {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    /// Results served from the offline cache
+    pub stale: usize,
+    pub mean_quality: Option<f64>,
+    pub min_quality: Option<f64>,
+    pub max_quality: Option<f64>,
+    pub mean_robust_pct: Option<f64>,
+    pub mean_time_ms: Option<f64>,
+    /// Occurrences of each violation across all items
+    pub violation_histogram: BTreeMap<String, usize>,
+    pub routing_paths: BTreeMap<String, usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalBatch {
+    pub id: String,
+    pub rubric_version: String,
+    pub status: BatchStatus,
+    pub max_concurrency: usize,
+    pub started_at: f64,
+    pub finished_at: Option<f64>,
+    pub items: Vec<ItemResult>,
+    pub report: BatchReport,
+}
+
+/// Payload of `unity:eval_batch_progress`
+#[derive(Debug, Clone, Serialize)]
+struct ProgressEvent<'a> {
+    batch_id: &'a str,
+    item: &'a ItemResult,
+    completed: usize,
+    total: usize,
+}
+
+fn report(items: &[ItemResult]) -> BatchReport {
+    let mut report = BatchReport {
+        total: items.len(),
+        ..Default::default()
+    };
+    let results: Vec<&EvaluateResponse> = items.iter().filter_map(|i| i.result.as_ref()).collect();
+
+    for item in items {
+        match item.status {
+            ItemStatus::Succeeded => report.succeeded += 1,
+            ItemStatus::Failed => report.failed += 1,
+            ItemStatus::Cancelled => report.cancelled += 1,
+            ItemStatus::Pending | ItemStatus::Running => {}
+        }
+    }
+    for result in &results {
+        if result.stale {
+            report.stale += 1;
+        }
+        for violation in &result.violations {
+            *report
+                .violation_histogram
+                .entry(violation.clone())
+                .or_default() += 1;
+        }
+        *report
+            .routing_paths
+            .entry(result.routing_path.clone())
+            .or_default() += 1;
+    }
+
+    let quality: Vec<f64> = results.iter().map(|r| r.quality_score).collect();
+    report.mean_quality = mean(&quality);
+    report.min_quality = quality.iter().copied().reduce(f64::min);
+    report.max_quality = quality.iter().copied().reduce(f64::max);
+    report.mean_robust_pct = mean(&results.iter().map(|r| r.robust_pct).collect::<Vec<_>>());
+    report.mean_time_ms = mean(&results.iter().map(|r| r.time_ms).collect::<Vec<_>>());
+    report
+}
+
+struct BatchEntry {
+    batch: EvalBatch,
+    cancel: watch::Sender<bool>,
+}
+
+/// Where a batch sends its evaluations
+#[derive(Clone)]
+struct Backend {
+    base_url: String,
+    available: bool,
+    cache: EvalCache,
+    history: EvolutionHistory,
+}
+
+/// Runs batches of evaluations in the background
+#[derive(Clone)]
+pub struct EvalBatchRunner {
+    app_handle: tauri::AppHandle,
+    batches: Arc<RwLock<HashMap<String, BatchEntry>>>,
+}
+
+impl EvalBatchRunner {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self {
+            app_handle,
+            batches: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    async fn start(
+        &self,
+        backend: Backend,
+        items: Vec<BatchItem>,
+        rubric_version: String,
+        max_concurrency: Option<usize>,
+    ) -> Result<String, String> {
+        if items.is_empty() {
+            return Err("No items to evaluate".to_string());
+        }
+        let max_concurrency = max_concurrency
+            .or_else(|| BudgetConfig::load().ok().and_then(|b| b.max_concurrency))
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1);
+
+        let batch = EvalBatch {
+            id: Uuid::new_v4().to_string(),
+            rubric_version: rubric_version.clone(),
+            status: BatchStatus::Running,
+            max_concurrency,
+            started_at: now_secs(),
+            finished_at: None,
+            items: items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| ItemResult {
+                    index,
+                    id: item.id.clone(),
+                    status: ItemStatus::Pending,
+                    result: None,
+                    error: None,
+                })
+                .collect(),
+            report: BatchReport::default(),
+        };
+        let batch_id = batch.id.clone();
+
+        let (cancel, cancel_rx) = watch::channel(false);
+        {
+            let mut batches = self.batches.write().await;
+            Self::prune(&mut batches);
+            batches.insert(batch_id.clone(), BatchEntry { batch, cancel });
+        }
+
+        let runner = self.clone();
+        let id = batch_id.clone();
+        tauri::async_runtime::spawn(async move {
+            runner
+                .run(
+                    &id,
+                    backend,
+                    items,
+                    rubric_version,
+                    max_concurrency,
+                    cancel_rx,
+                )
+                .await;
+        });
+        Ok(batch_id)
+    }
+
+    fn prune(batches: &mut HashMap<String, BatchEntry>) {
+        let mut finished: Vec<(String, f64)> = batches
+            .values()
+            .filter_map(|e| e.batch.finished_at.map(|t| (e.batch.id.clone(), t)))
+            .collect();
+        if finished.len() < MAX_FINISHED_BATCHES {
+            return;
+        }
+        finished.sort_by(|a, b| a.1.total_cmp(&b.1));
+        for (id, _) in finished
+            .iter()
+            .take(finished.len() + 1 - MAX_FINISHED_BATCHES)
+        {
+            batches.remove(id);
+        }
+    }
+
+    async fn run(
+        &self,
+        batch_id: &str,
+        backend: Backend,
+        items: Vec<BatchItem>,
+        rubric_version: String,
+        max_concurrency: usize,
+        mut cancel: watch::Receiver<bool>,
+    ) {
+        let total = items.len();
+        let mut queue = items.into_iter().enumerate();
+        let mut running: JoinSet<(usize, Result<EvaluateResponse, String>)> = JoinSet::new();
+        let mut running_ids: HashMap<task::Id, usize> = HashMap::new();
+        let mut cancelled = false;
+
+        loop {
+            while !cancelled && running.len() < max_concurrency {
+                let Some((index, item)) = queue.next() else {
+                    break;
+                };
+                self.update_item(batch_id, index, total, |i| i.status = ItemStatus::Running)
+                    .await;
+
+                let backend = backend.clone();
+                let request = EvaluateRequest {
+                    goal: item.goal,
+                    output: item.output,
+                    rubric_version: rubric_version.clone(),
+                };
+                let handle = running.spawn(async move {
+                    let result = backend
+                        .cache
+                        .evaluate(&backend.base_url, backend.available, &request)
+                        .await;
+                    if let Ok(response) = &result {
+                        if !response.stale {
+                            if let Err(e) = backend.history.record_evaluation(
+                                &request,
+                                response,
+                                item.variant_id,
+                            ) {
+                                eprintln!("[Unity] {}", e);
+                            }
+                        }
+                    }
+                    (index, result.map_err(|e| e.to_string()))
+                });
+                running_ids.insert(handle.id(), index);
+            }
+
+            if running.is_empty() {
+                break;
+            }
+
+            tokio::select! {
+                _ = cancel.changed(), if !cancelled => {
+                    cancelled = true;
+                    running.abort_all();
+                }
+                joined = running.join_next_with_id() => match joined {
+                    Some(Ok((id, (index, result)))) => {
+                        running_ids.remove(&id);
+                        self.update_item(batch_id, index, total, |item| match result {
+                            Ok(response) => {
+                                item.status = ItemStatus::Succeeded;
+                                item.result = Some(response);
+                            }
+                            Err(e) => {
+                                item.status = ItemStatus::Failed;
+                                item.error = Some(e);
+                            }
+                        })
+                        .await;
+                    }
+                    // Debug builds only; release builds abort on panic
+                    Some(Err(join_error)) if join_error.is_panic() => {
+                        if let Some(index) = running_ids.remove(&join_error.id()) {
+                            let error =
+                                format!("Evaluation panicked: {}", panic_message(join_error));
+                            self.update_item(batch_id, index, total, |item| {
+                                item.status = ItemStatus::Failed;
+                                item.error = Some(error);
+                            })
+                            .await;
+                        }
+                    }
+                    // Aborted items stay running and are marked cancelled below
+                    Some(Err(_)) | None => {}
+                }
+            }
+        }
+
+        let mut batches = self.batches.write().await;
+        let Some(entry) = batches.get_mut(batch_id) else {
+            return;
+        };
+        let batch = &mut entry.batch;
+        for item in batch.items.iter_mut() {
+            if matches!(item.status, ItemStatus::Pending | ItemStatus::Running) {
+                item.status = ItemStatus::Cancelled;
+            }
+        }
+        batch.status = if cancelled {
+            BatchStatus::Cancelled
+        } else {
+            BatchStatus::Completed
+        };
+        batch.finished_at = Some(now_secs());
+        batch.report = report(&batch.items);
+        self.app_handle
+            .emit_all("unity:eval_batch_finished", &*batch)
+            .ok();
+    }
+
+    async fn update_item(
+        &self,
+        batch_id: &str,
+        index: usize,
+        total: usize,
+        update: impl FnOnce(&mut ItemResult),
+    ) {
+        let mut batches = self.batches.write().await;
+        let Some(entry) = batches.get_mut(batch_id) else {
+            return;
+        };
+        let Some(item) = entry.batch.items.get_mut(index) else {
+            return;
+        };
+        update(item);
+
+        let completed = entry
+            .batch
+            .items
+            .iter()
+            .filter(|i| matches!(i.status, ItemStatus::Succeeded | ItemStatus::Failed))
+            .count();
+        let event = ProgressEvent {
+            batch_id,
+            item: &entry.batch.items[index],
+            completed,
+            total,
+        };
+        self.app_handle
+            .emit_all("unity:eval_batch_progress", event)
+            .ok();
+    }
+
+    pub async fn get(&self, batch_id: &str) -> Option<EvalBatch> {
+        let batches = self.batches.read().await;
+        let mut batch = batches.get(batch_id)?.batch.clone();
+        if batch.status == BatchStatus::Running {
+            batch.report = report(&batch.items);
+        }
+        Some(batch)
+    }
+
+    pub async fn cancel(&self, batch_id: &str) -> Result<(), String> {
+        let batches = self.batches.read().await;
+        let entry = batches
+            .get(batch_id)
+            .ok_or_else(|| format!("Evaluation batch not found: {}", batch_id))?;
+        if entry.batch.status != BatchStatus::Running {
+            return Err(format!("Evaluation batch already finished: {}", batch_id));
+        }
+        entry.cancel.send_replace(true);
+        Ok(())
+    }
+}
+
+/// Tauri commands for batch evaluation
+#[tauri::command]
+pub async fn evaluate_batch(
+    state: tauri::State<'_, AppState>,
+    runner: tauri::State<'_, EvalBatchRunner>,
+    cache: tauri::State<'_, EvalCache>,
+    history: tauri::State<'_, EvolutionHistory>,
+    items: Vec<BatchItem>,
+    rubric_version: String,
+    max_concurrency: Option<usize>,
+) -> Result<String, String> {
+    let backend = Backend {
+        base_url: state.backend_base_url.clone(),
+        available: *state.preflight_passed.lock().unwrap(),
+        cache: cache.inner().clone(),
+        history: history.inner().clone(),
+    };
+    runner
+        .start(backend, items, rubric_version, max_concurrency)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_eval_batch(
+    runner: tauri::State<'_, EvalBatchRunner>,
+    batch_id: String,
+) -> Result<EvalBatch, String> {
+    runner
+        .get(&batch_id)
+        .await
+        .ok_or_else(|| format!("Evaluation batch not found: {}", batch_id))
+}
+
+#[tauri::command]
+pub async fn cancel_eval_batch(
+    runner: tauri::State<'_, EvalBatchRunner>,
+    batch_id: String,
+) -> Result<(), String> {
+    runner.cancel(&batch_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(index: usize, status: ItemStatus, result: Option<(f64, &[&str], bool)>) -> ItemResult {
+        ItemResult {
+            index,
+            id: None,
+            status,
+            result: result.map(|(quality, violations, stale)| EvaluateResponse {
+                quality_score: quality,
+                delta_score: 0.0,
+                robust_pct: 50.0,
+                cache_hit: stale,
+                time_ms: 10.0,
+                routing_path: if stale { "cache" } else { "local" }.to_string(),
+                violations: violations.iter().map(|v| v.to_string()).collect(),
+                stale,
+                cached_at: None,
+                expired: false,
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn report_counts_statuses_violations_and_stale_results() {
+        let items = [
+            item(
+                0,
+                ItemStatus::Succeeded,
+                Some((0.8, &["too_long", "no_tests"], false)),
+            ),
+            item(1, ItemStatus::Succeeded, Some((0.4, &["too_long"], true))),
+            item(2, ItemStatus::Failed, None),
+            item(3, ItemStatus::Cancelled, None),
+            item(4, ItemStatus::Pending, None),
+        ];
+
+        let report = report(&items);
+
+        assert_eq!(report.total, 5);
+        assert_eq!(
+            (report.succeeded, report.failed, report.cancelled),
+            (2, 1, 1)
+        );
+        assert_eq!(report.stale, 1);
+        assert_eq!(report.violation_histogram["too_long"], 2);
+        assert_eq!(report.violation_histogram["no_tests"], 1);
+        assert_eq!(report.violation_histogram.len(), 2);
+        assert_eq!(report.routing_paths["local"], 1);
+        assert_eq!(report.routing_paths["cache"], 1);
+        assert!((report.mean_quality.unwrap() - 0.6).abs() < 1e-9);
+        assert_eq!(report.min_quality, Some(0.4));
+        assert_eq!(report.max_quality, Some(0.8));
+    }
+
+    #[test]
+    fn report_without_results_has_no_averages() {
+        let report = report(&[item(0, ItemStatus::Failed, None)]);
+
+        assert_eq!((report.total, report.failed, report.stale), (1, 1, 0));
+        assert!(report.violation_histogram.is_empty());
+        assert_eq!(report.mean_quality, None);
+        assert_eq!(report.mean_time_ms, None);
+    }
+}
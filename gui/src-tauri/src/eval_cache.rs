@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::configs::{EvalCacheConfig, EvalConfig};
+use crate::schemas::{self, IpcError};
+use crate::util::{now_secs, write_atomic};
+use crate::{EvaluateRequest, EvaluateResponse};
+
+/// File (inside the app data dir) holding cached evaluations
+const CACHE_FILE: &str = "eval_cache.json";
+
+/// One evaluation, addressed by its (goal, output, rubric_version) hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    goal: String,
+    rubric_version: String,
+    response: EvaluateResponse,
+    cached_at: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalCacheStats {
+    pub enabled: bool,
+    pub entries: usize,
+    /// Entries past `ttl_seconds`, kept only as an offline fallback
+    pub expired: usize,
+    pub max_entries: usize,
+    pub ttl_seconds: u64,
+    pub stale_max_age_seconds: u64,
+    pub oldest: Option<f64>,
+    pub newest: Option<f64>,
+}
+
+/// Why an evaluation could not be fetched from the backend
+pub enum FetchError {
+    /// Backend down, timed out or failing; a cached result may stand in
+    Unreachable(String),
+    /// The backend answered, but not with a usable evaluation
+    Rejected(IpcError),
+}
+
+pub fn cache_key(request: &EvaluateRequest) -> String {
+    let mut hasher = Sha256::new();
+    for part in [&request.goal, &request.output, &request.rubric_version] {
+        hasher.update(part.as_bytes());
+        hasher.update([0u8]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Ask the backend to evaluate `request`
+pub async fn fetch_evaluation(
+    backend_base_url: &str,
+    request: &EvaluateRequest,
+) -> Result<EvaluateResponse, FetchError> {
+    let response = reqwest::Client::new()
+        .post(format!("{}/evaluate", backend_base_url))
+        .json(request)
+        .timeout(Duration::from_secs(60))
+        .send()
+        .await
+        .map_err(|e| FetchError::Unreachable(format!("Request failed: {}", e)))?;
+
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(FetchError::Unreachable(format!(
+            "Backend error: {}",
+            status
+        )));
+    }
+    if !status.is_success() {
+        return Err(FetchError::Rejected(
+            format!("Backend error: {}", status).into(),
+        ));
+    }
+
+    let body = response
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| FetchError::Rejected(format!("Failed to parse response: {}", e).into()))?;
+    schemas::decode_node_outputs(body).map_err(|e| FetchError::Rejected(e.into()))
+}
+
+/// Local copy of past evaluations so results can still be shown while the
+/// backend is unreachable. Entries past eval.yaml `CACHE.ttl_seconds` are
+/// flagged as expired and evicted after `stale_max_age_seconds`; `max_entries`
+/// bounds the size
+#[derive(Clone)]
+pub struct EvalCache {
+    config: EvalCacheConfig,
+    path: Option<PathBuf>,
+    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+}
+
+impl EvalCache {
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let config = EvalConfig::load().map(|c| c.cache).unwrap_or_default();
+        let path = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(CACHE_FILE));
+
+        let entries: HashMap<String, CacheEntry> = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| {
+                serde_json::from_str(&raw)
+                    .map_err(|e| eprintln!("[Unity] Ignoring corrupt evaluation cache: {}", e))
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        let cache = Self {
+            config,
+            path,
+            entries: Arc::new(Mutex::new(entries)),
+        };
+        cache.prune(&mut cache.entries.lock().unwrap());
+        cache
+    }
+
+    fn is_expired(&self, entry: &CacheEntry, now: f64) -> bool {
+        now - entry.cached_at > self.config.ttl_seconds as f64
+    }
+
+    /// Drop entries past `stale_max_age_seconds`, then the oldest ones beyond
+    /// `max_entries`
+    fn prune(&self, entries: &mut HashMap<String, CacheEntry>) {
+        let max_age = self
+            .config
+            .stale_max_age_seconds
+            .max(self.config.ttl_seconds);
+        let cutoff = now_secs() - max_age as f64;
+        entries.retain(|_, e| e.cached_at >= cutoff);
+
+        if entries.len() > self.config.max_entries {
+            let mut by_age: Vec<(String, f64)> = entries
+                .iter()
+                .map(|(k, e)| (k.clone(), e.cached_at))
+                .collect();
+            by_age.sort_by(|a, b| a.1.total_cmp(&b.1));
+            let excess = entries.len() - self.config.max_entries;
+            for (key, _) in by_age.into_iter().take(excess) {
+                entries.remove(&key);
+            }
+        }
+    }
+
+    fn persist(&self, entries: &HashMap<String, CacheEntry>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map(std::fs::create_dir_all)
+            .unwrap_or(Ok(()))
+            .and_then(|_| {
+                let raw = serde_json::to_vec(entries)?;
+                write_atomic(path, raw)
+            });
+        if let Err(e) = result {
+            eprintln!("[Unity] Failed to save evaluation cache: {}", e);
+        }
+    }
+
+    pub fn store(&self, request: &EvaluateRequest, response: &EvaluateResponse) {
+        if !self.config.enabled {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(
+            cache_key(request),
+            CacheEntry {
+                goal: request.goal.clone(),
+                rubric_version: request.rubric_version.clone(),
+                response: response.clone(),
+                cached_at: now_secs(),
+            },
+        );
+        self.prune(&mut entries);
+        self.persist(&entries);
+    }
+
+    /// A previous evaluation of the same request, flagged as stale and as
+    /// expired once it is older than `ttl_seconds`
+    pub fn lookup(&self, request: &EvaluateRequest) -> Option<EvaluateResponse> {
+        if !self.config.enabled {
+            return None;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        self.prune(&mut entries);
+        let now = now_secs();
+
+        entries
+            .get(&cache_key(request))
+            .map(|entry| EvaluateResponse {
+                stale: true,
+                cached_at: Some(entry.cached_at),
+                expired: self.is_expired(entry, now),
+                ..entry.response.clone()
+            })
+    }
+
+    /// Evaluate on the backend, caching the result; if the backend cannot be
+    /// reached, serve the cached evaluation instead
+    pub async fn evaluate(
+        &self,
+        backend_base_url: &str,
+        backend_available: bool,
+        request: &EvaluateRequest,
+    ) -> Result<EvaluateResponse, IpcError> {
+        let fetched = if backend_available {
+            fetch_evaluation(backend_base_url, request).await
+        } else {
+            Err(FetchError::Unreachable(
+                "Preflight checks failed - run diagnostics first".to_string(),
+            ))
+        };
+
+        match fetched {
+            Ok(response) => {
+                self.store(request, &response);
+                Ok(response)
+            }
+            Err(FetchError::Unreachable(message)) => {
+                self.lookup(request).ok_or_else(|| message.into())
+            }
+            Err(FetchError::Rejected(e)) => Err(e),
+        }
+    }
+
+    pub fn stats(&self) -> EvalCacheStats {
+        let mut entries = self.entries.lock().unwrap();
+        self.prune(&mut entries);
+        let now = now_secs();
+        let times = entries.values().map(|e| e.cached_at);
+
+        EvalCacheStats {
+            enabled: self.config.enabled,
+            entries: entries.len(),
+            expired: entries.values().filter(|e| self.is_expired(e, now)).count(),
+            max_entries: self.config.max_entries,
+            ttl_seconds: self.config.ttl_seconds,
+            stale_max_age_seconds: self.config.stale_max_age_seconds,
+            oldest: times.clone().reduce(f64::min),
+            newest: times.reduce(f64::max),
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.clear();
+        self.persist(&entries);
+    }
+}
+
+/// Tauri commands for the offline evaluation cache
+#[tauri::command]
+pub async fn get_cached_evaluation(
+    cache: tauri::State<'_, EvalCache>,
+    request: EvaluateRequest,
+) -> Result<Option<EvaluateResponse>, String> {
+    Ok(cache.lookup(&request))
+}
+
+#[tauri::command]
+pub async fn get_eval_cache_stats(
+    cache: tauri::State<'_, EvalCache>,
+) -> Result<EvalCacheStats, String> {
+    Ok(cache.stats())
+}
+
+#[tauri::command]
+pub async fn clear_eval_cache(cache: tauri::State<'_, EvalCache>) -> Result<(), String> {
+    cache.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_entries: usize) -> EvalCache {
+        EvalCache {
+            config: EvalCacheConfig {
+                enabled: true,
+                max_entries,
+                ttl_seconds: 3600,
+                stale_max_age_seconds: 86_400,
+            },
+            path: None,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn request(output: &str) -> EvaluateRequest {
+        EvaluateRequest {
+            goal: "summarise".to_string(),
+            output: output.to_string(),
+            rubric_version: "v1".to_string(),
+        }
+    }
+
+    fn response() -> EvaluateResponse {
+        EvaluateResponse {
+            quality_score: 0.8,
+            delta_score: 0.1,
+            robust_pct: 90.0,
+            cache_hit: false,
+            time_ms: 12.0,
+            routing_path: "fast".to_string(),
+            violations: Vec::new(),
+            stale: false,
+            cached_at: None,
+            expired: false,
+        }
+    }
+
+    fn backdate(cache: &EvalCache, request: &EvaluateRequest, secs: f64) {
+        let mut entries = cache.entries.lock().unwrap();
+        entries.get_mut(&cache_key(request)).unwrap().cached_at -= secs;
+    }
+
+    #[test]
+    fn expired_entries_are_kept_for_offline_use() {
+        let cache = cache(10);
+        let old = request("old");
+        cache.store(&old, &response());
+        cache.store(&request("new"), &response());
+        backdate(&cache, &old, 7200.0);
+
+        let served = cache.lookup(&old).expect("expired entry still served");
+        assert!(served.stale && served.expired);
+        assert!(!cache.lookup(&request("new")).unwrap().expired);
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.expired), (2, 1));
+    }
+
+    #[test]
+    fn entries_past_the_stale_max_age_are_evicted() {
+        let cache = cache(10);
+        let ancient = request("ancient");
+        cache.store(&ancient, &response());
+        backdate(&cache, &ancient, 2.0 * 86_400.0);
+
+        assert!(cache.lookup(&ancient).is_none());
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn oldest_entries_are_evicted_beyond_max_entries() {
+        let cache = cache(2);
+        let first = request("first");
+        cache.store(&first, &response());
+        backdate(&cache, &first, 10.0);
+        cache.store(&request("second"), &response());
+        cache.store(&request("third"), &response());
+
+        assert!(cache.lookup(&first).is_none());
+        assert!(cache.lookup(&request("second")).is_some());
+        assert_eq!(cache.stats().entries, 2);
+    }
+}
@@ -0,0 +1,783 @@
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use crate::util::now_secs;
+use crate::{BanditStatus, EvaluateRequest, EvaluateResponse, MutateRequest, MutateResponse};
+
+/// SQLite database (inside the app data dir) holding mutation/evaluation history
+const DB_FILE: &str = "evolution_history.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS variants (
+    variant_id TEXT PRIMARY KEY,
+    goal TEXT NOT NULL,
+    parent_variant_id TEXT,
+    arm TEXT NOT NULL,
+    delta_score REAL NOT NULL,
+    novelty REAL NOT NULL,
+    workflow TEXT NOT NULL,
+    created_at REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS variants_goal ON variants (goal, created_at);
+CREATE INDEX IF NOT EXISTS variants_parent ON variants (parent_variant_id);
+
+CREATE TABLE IF NOT EXISTS evaluations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    variant_id TEXT,
+    goal TEXT NOT NULL,
+    rubric_version TEXT NOT NULL,
+    quality_score REAL NOT NULL,
+    delta_score REAL NOT NULL,
+    robust_pct REAL NOT NULL,
+    cache_hit INTEGER NOT NULL,
+    time_ms REAL NOT NULL,
+    routing_path TEXT NOT NULL,
+    violations TEXT NOT NULL,
+    created_at REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS evaluations_goal ON evaluations (goal, created_at);
+CREATE INDEX IF NOT EXISTS evaluations_variant ON evaluations (variant_id);
+
+CREATE TABLE IF NOT EXISTS bandit_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    arm TEXT NOT NULL,
+    pulls INTEGER NOT NULL,
+    total_reward REAL NOT NULL,
+    total_pulls INTEGER NOT NULL,
+    created_at REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS bandit_snapshots_time ON bandit_snapshots (created_at);
+";
+
+/// Trend bucket size when none is given: one day
+const DEFAULT_BUCKET_SECS: f64 = 86_400.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantRecord {
+    pub variant_id: String,
+    pub goal: String,
+    pub parent_variant_id: Option<String>,
+    pub arm: String,
+    pub delta_score: f64,
+    pub novelty: f64,
+    pub workflow: String,
+    pub created_at: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationRecord {
+    pub id: i64,
+    pub variant_id: Option<String>,
+    pub goal: String,
+    pub rubric_version: String,
+    pub quality_score: f64,
+    pub delta_score: f64,
+    pub robust_pct: f64,
+    pub cache_hit: bool,
+    pub time_ms: f64,
+    pub routing_path: String,
+    pub violations: Vec<String>,
+    pub created_at: f64,
+}
+
+/// A variant with its evaluations and descendants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineageNode {
+    pub variant: VariantRecord,
+    /// Best quality score among the variant's evaluations
+    pub best_quality: Option<f64>,
+    pub evaluations: usize,
+    pub children: Vec<LineageNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BestVariant {
+    pub goal: String,
+    pub variant: VariantRecord,
+    pub quality_score: f64,
+    pub evaluation_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPoint {
+    /// Start of the bucket (unix seconds)
+    pub bucket_start: f64,
+    pub evaluations: usize,
+    pub mean_quality: f64,
+    pub max_quality: f64,
+    pub mean_robust_pct: f64,
+    pub mean_delta: f64,
+}
+
+/// Backend bandit counters for one arm at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanditSnapshot {
+    pub arm: String,
+    pub pulls: i64,
+    pub total_reward: f64,
+    pub total_pulls: i64,
+    pub created_at: f64,
+}
+
+/// One arm pull as seen locally: a variant and the score delta it achieved
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArmPull {
+    pub variant_id: String,
+    pub goal: String,
+    pub arm: String,
+    /// Delta of the variant's latest evaluation, else the mutation's own delta
+    pub delta_score: f64,
+    pub evaluated: bool,
+    pub created_at: f64,
+}
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("Evolution history error: {}", e)
+}
+
+const VARIANT_COLUMNS: &str =
+    "variant_id, goal, parent_variant_id, arm, delta_score, novelty, workflow, created_at";
+
+fn variant_from_row(row: &Row) -> rusqlite::Result<VariantRecord> {
+    Ok(VariantRecord {
+        variant_id: row.get(0)?,
+        goal: row.get(1)?,
+        parent_variant_id: row.get(2)?,
+        arm: row.get(3)?,
+        delta_score: row.get(4)?,
+        novelty: row.get(5)?,
+        workflow: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+const EVALUATION_COLUMNS: &str = "id, variant_id, goal, rubric_version, quality_score, delta_score, robust_pct, cache_hit, time_ms, routing_path, violations, created_at";
+
+fn evaluation_from_row(row: &Row) -> rusqlite::Result<EvaluationRecord> {
+    let violations: String = row.get(10)?;
+    Ok(EvaluationRecord {
+        id: row.get(0)?,
+        variant_id: row.get(1)?,
+        goal: row.get(2)?,
+        rubric_version: row.get(3)?,
+        quality_score: row.get(4)?,
+        delta_score: row.get(5)?,
+        robust_pct: row.get(6)?,
+        cache_hit: row.get(7)?,
+        time_ms: row.get(8)?,
+        routing_path: row.get(9)?,
+        violations: serde_json::from_str(&violations).unwrap_or_default(),
+        created_at: row.get(11)?,
+    })
+}
+
+/// Embedded record of every mutation and evaluation
+#[derive(Clone)]
+pub struct EvolutionHistory {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl EvolutionHistory {
+    /// Open the database in the app data dir, falling back to an in-memory
+    /// one so the app still runs when the file cannot be opened
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let conn = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .ok_or_else(|| "No app data directory".to_string())
+            .and_then(|dir| Self::open(&dir.join(DB_FILE)))
+            .unwrap_or_else(|e| {
+                eprintln!("[Unity] {}; keeping evolution history in memory", e);
+                Self::open_in_memory()
+            });
+
+        Self {
+            conn: Arc::new(Mutex::new(conn)),
+        }
+    }
+
+    fn open_in_memory() -> Connection {
+        let conn = Connection::open_in_memory().expect("in-memory SQLite");
+        conn.execute_batch(SCHEMA)
+            .expect("evolution history schema");
+        conn
+    }
+
+    fn open(path: &Path) -> Result<Connection, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        conn.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(conn)
+    }
+
+    /// Record a mutation. Without an explicit parent, the latest variant of
+    /// the same goal whose workflow is `current_workflow` is taken as parent.
+    pub fn record_mutation(
+        &self,
+        request: &MutateRequest,
+        response: &MutateResponse,
+        parent_variant_id: Option<String>,
+    ) -> Result<(), String> {
+        let conn = self.conn.lock().unwrap();
+        let parent_variant_id = match parent_variant_id {
+            Some(parent) => Some(parent),
+            None => conn
+                .query_row(
+                    "SELECT variant_id FROM variants WHERE goal = ?1 AND workflow = ?2
+                     ORDER BY created_at DESC LIMIT 1",
+                    params![request.goal, request.current_workflow],
+                    |row| row.get(0),
+                )
+                .optional()
+                .map_err(db_error)?,
+        };
+
+        conn.execute(
+            "INSERT OR REPLACE INTO variants
+             (variant_id, goal, parent_variant_id, arm, delta_score, novelty, workflow, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                response.variant_id,
+                request.goal,
+                parent_variant_id,
+                response.arm,
+                response.delta_score,
+                response.novelty,
+                response.workflow,
+                now_secs()
+            ],
+        )
+        .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn record_evaluation(
+        &self,
+        request: &EvaluateRequest,
+        response: &EvaluateResponse,
+        variant_id: Option<String>,
+    ) -> Result<(), String> {
+        let violations = serde_json::to_string(&response.violations).unwrap_or_default();
+        self.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "INSERT INTO evaluations
+                 (variant_id, goal, rubric_version, quality_score, delta_score, robust_pct,
+                  cache_hit, time_ms, routing_path, violations, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    variant_id,
+                    request.goal,
+                    request.rubric_version,
+                    response.quality_score,
+                    response.delta_score,
+                    response.robust_pct,
+                    response.cache_hit,
+                    response.time_ms,
+                    response.routing_path,
+                    violations,
+                    now_secs()
+                ],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    pub fn variants(&self, goal: Option<&str>, limit: usize) -> Result<Vec<VariantRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM variants WHERE ?1 IS NULL OR goal = ?1
+                 ORDER BY created_at DESC LIMIT ?2",
+                VARIANT_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![goal, limit as i64], variant_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    pub fn evaluations(
+        &self,
+        goal: Option<&str>,
+        variant_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<EvaluationRecord>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM evaluations
+                 WHERE (?1 IS NULL OR goal = ?1) AND (?2 IS NULL OR variant_id = ?2)
+                 ORDER BY created_at DESC LIMIT ?3",
+                EVALUATION_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![goal, variant_id, limit as i64], evaluation_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Lineage trees: the tree containing `variant_id`, or every root of `goal`
+    pub fn lineage(
+        &self,
+        goal: Option<&str>,
+        variant_id: Option<&str>,
+    ) -> Result<Vec<LineageNode>, String> {
+        let conn = self.conn.lock().unwrap();
+
+        let goal = match variant_id {
+            Some(id) => Some(
+                conn.query_row(
+                    "SELECT goal FROM variants WHERE variant_id = ?1",
+                    params![id],
+                    |row| row.get::<_, String>(0),
+                )
+                .optional()
+                .map_err(db_error)?
+                .ok_or_else(|| format!("Variant not found: {}", id))?,
+            ),
+            None => goal.map(str::to_string),
+        };
+
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {} FROM variants WHERE ?1 IS NULL OR goal = ?1 ORDER BY created_at",
+                VARIANT_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let variants: Vec<VariantRecord> = stmt
+            .query_map(params![goal], variant_from_row)
+            .map_err(db_error)?
+            .collect::<Result<_, _>>()
+            .map_err(db_error)?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT variant_id, COUNT(*), MAX(quality_score) FROM evaluations
+                 WHERE variant_id IS NOT NULL GROUP BY variant_id",
+            )
+            .map_err(db_error)?;
+        let scores: HashMap<String, (usize, f64)> = stmt
+            .query_map([], |row| {
+                Ok((row.get(0)?, (row.get::<_, i64>(1)? as usize, row.get(2)?)))
+            })
+            .map_err(db_error)?
+            .collect::<Result<_, _>>()
+            .map_err(db_error)?;
+
+        let known: HashMap<&str, &VariantRecord> = variants
+            .iter()
+            .map(|v| (v.variant_id.as_str(), v))
+            .collect();
+        let mut children: HashMap<&str, Vec<&VariantRecord>> = HashMap::new();
+        let mut roots = Vec::new();
+        for variant in &variants {
+            match variant.parent_variant_id.as_deref() {
+                Some(parent) if known.contains_key(parent) && parent != variant.variant_id => {
+                    children.entry(parent).or_default().push(variant)
+                }
+                _ => roots.push(variant),
+            }
+        }
+
+        // Only the root of the requested variant's tree
+        if let Some(id) = variant_id {
+            let mut root = id;
+            let mut steps = 0;
+            while let Some(parent) = known[root].parent_variant_id.as_deref() {
+                if !known.contains_key(parent) || steps > variants.len() {
+                    break;
+                }
+                root = parent;
+                steps += 1;
+            }
+            roots.retain(|v| v.variant_id == root);
+        }
+
+        fn build(
+            variant: &VariantRecord,
+            children: &HashMap<&str, Vec<&VariantRecord>>,
+            scores: &HashMap<String, (usize, f64)>,
+        ) -> LineageNode {
+            let (evaluations, best_quality) = match scores.get(&variant.variant_id) {
+                Some(&(count, best)) => (count, Some(best)),
+                None => (0, None),
+            };
+            LineageNode {
+                variant: variant.clone(),
+                best_quality,
+                evaluations,
+                children: children
+                    .get(variant.variant_id.as_str())
+                    .map(|kids| kids.iter().map(|k| build(k, children, scores)).collect())
+                    .unwrap_or_default(),
+            }
+        }
+
+        Ok(roots
+            .into_iter()
+            .map(|root| build(root, &children, &scores))
+            .collect())
+    }
+
+    /// Highest-scoring evaluated variant per goal
+    pub fn best_variants(&self, goal: Option<&str>) -> Result<Vec<BestVariant>, String> {
+        let conn = self.conn.lock().unwrap();
+        let columns: Vec<String> = VARIANT_COLUMNS
+            .split(", ")
+            .map(|c| format!("v.{}", c))
+            .collect();
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT {}, e.quality_score, e.id FROM evaluations e
+                 JOIN variants v ON v.variant_id = e.variant_id
+                 WHERE (?1 IS NULL OR v.goal = ?1)
+                 AND e.id = (
+                     SELECT e2.id FROM evaluations e2
+                     JOIN variants v2 ON v2.variant_id = e2.variant_id
+                     WHERE v2.goal = v.goal
+                     ORDER BY e2.quality_score DESC, e2.created_at DESC LIMIT 1
+                 )
+                 ORDER BY v.goal",
+                columns.join(", ")
+            ))
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![goal], |row| {
+                let variant = variant_from_row(row)?;
+                Ok(BestVariant {
+                    goal: variant.goal.clone(),
+                    variant,
+                    quality_score: row.get(8)?,
+                    evaluation_id: row.get(9)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Snapshot the backend bandit counters, unless nothing was pulled since
+    /// the last snapshot
+    pub fn record_bandit_status(&self, status: &BanditStatus) -> Result<(), String> {
+        let mut conn = self.conn.lock().unwrap();
+        let last_total: Option<i64> = conn
+            .query_row(
+                "SELECT total_pulls FROM bandit_snapshots ORDER BY created_at DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)?;
+        if last_total == Some(status.total_pulls as i64) {
+            return Ok(());
+        }
+
+        let tx = conn.transaction().map_err(db_error)?;
+        let created_at = now_secs();
+        for (arm, pulls) in &status.arm_counts {
+            tx.execute(
+                "INSERT INTO bandit_snapshots (arm, pulls, total_reward, total_pulls, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    arm,
+                    pulls,
+                    status.arm_rewards.get(arm).copied().unwrap_or(0.0),
+                    status.total_pulls,
+                    created_at
+                ],
+            )
+            .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    /// Bandit snapshots, oldest first
+    pub fn bandit_snapshots(&self, since: Option<f64>) -> Result<Vec<BanditSnapshot>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT arm, pulls, total_reward, total_pulls, created_at FROM bandit_snapshots
+                 WHERE ?1 IS NULL OR created_at >= ?1 ORDER BY created_at, arm",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![since], |row| {
+                Ok(BanditSnapshot {
+                    arm: row.get(0)?,
+                    pulls: row.get(1)?,
+                    total_reward: row.get(2)?,
+                    total_pulls: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Every recorded mutation with the delta it earned, oldest first
+    pub fn arm_pulls(
+        &self,
+        goal: Option<&str>,
+        since: Option<f64>,
+    ) -> Result<Vec<ArmPull>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT v.variant_id, v.goal, v.arm,
+                        (SELECT e.delta_score FROM evaluations e WHERE e.variant_id = v.variant_id
+                         ORDER BY e.created_at DESC LIMIT 1),
+                        v.delta_score, v.created_at
+                 FROM variants v
+                 WHERE (?1 IS NULL OR v.goal = ?1) AND (?2 IS NULL OR v.created_at >= ?2)
+                 ORDER BY v.created_at",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![goal, since], |row| {
+                let evaluated: Option<f64> = row.get(3)?;
+                Ok(ArmPull {
+                    variant_id: row.get(0)?,
+                    goal: row.get(1)?,
+                    arm: row.get(2)?,
+                    delta_score: evaluated.unwrap_or(row.get(4)?),
+                    evaluated: evaluated.is_some(),
+                    created_at: row.get(5)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Evaluation scores bucketed over time, oldest first
+    pub fn score_trend(
+        &self,
+        goal: Option<&str>,
+        bucket_secs: f64,
+        since: Option<f64>,
+    ) -> Result<Vec<TrendPoint>, String> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn
+            .prepare(
+                "SELECT CAST(created_at / ?1 AS INTEGER) AS bucket, COUNT(*),
+                        AVG(quality_score), MAX(quality_score), AVG(robust_pct), AVG(delta_score)
+                 FROM evaluations
+                 WHERE (?2 IS NULL OR goal = ?2) AND (?3 IS NULL OR created_at >= ?3)
+                 GROUP BY bucket ORDER BY bucket",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map(params![bucket_secs, goal, since], |row| {
+                Ok(TrendPoint {
+                    bucket_start: row.get::<_, i64>(0)? as f64 * bucket_secs,
+                    evaluations: row.get::<_, i64>(1)? as usize,
+                    mean_quality: row.get(2)?,
+                    max_quality: row.get(3)?,
+                    mean_robust_pct: row.get(4)?,
+                    mean_delta: row.get(5)?,
+                })
+            })
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+}
+
+/// Tauri commands for auditing workflow evolution
+#[tauri::command]
+pub async fn list_evolution_variants(
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<VariantRecord>, String> {
+    history.variants(goal.as_deref(), limit.unwrap_or(100))
+}
+
+#[tauri::command]
+pub async fn list_evolution_evaluations(
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+    variant_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<EvaluationRecord>, String> {
+    history.evaluations(goal.as_deref(), variant_id.as_deref(), limit.unwrap_or(100))
+}
+
+#[tauri::command]
+pub async fn get_variant_lineage(
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+    variant_id: Option<String>,
+) -> Result<Vec<LineageNode>, String> {
+    history.lineage(goal.as_deref(), variant_id.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_best_variants(
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+) -> Result<Vec<BestVariant>, String> {
+    history.best_variants(goal.as_deref())
+}
+
+#[tauri::command]
+pub async fn get_score_trend(
+    history: tauri::State<'_, EvolutionHistory>,
+    goal: Option<String>,
+    bucket_secs: Option<f64>,
+    since: Option<f64>,
+) -> Result<Vec<TrendPoint>, String> {
+    let bucket_secs = bucket_secs.unwrap_or(DEFAULT_BUCKET_SECS);
+    if bucket_secs <= 0.0 {
+        return Err("bucket_secs must be positive".to_string());
+    }
+    history.score_trend(goal.as_deref(), bucket_secs, since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history() -> EvolutionHistory {
+        EvolutionHistory {
+            conn: Arc::new(Mutex::new(EvolutionHistory::open_in_memory())),
+        }
+    }
+
+    fn mutate(history: &EvolutionHistory, goal: &str, id: &str, parent: Option<&str>) {
+        let request = MutateRequest {
+            goal: goal.to_string(),
+            current_workflow: String::new(),
+            arm: None,
+        };
+        let response = MutateResponse {
+            variant_id: id.to_string(),
+            arm: "explore".to_string(),
+            delta_score: 0.0,
+            novelty: 0.0,
+            workflow: format!("workflow of {}", id),
+        };
+        history
+            .record_mutation(&request, &response, parent.map(str::to_string))
+            .unwrap();
+    }
+
+    fn evaluate(history: &EvolutionHistory, goal: &str, id: Option<&str>, quality: f64) {
+        let request = EvaluateRequest {
+            goal: goal.to_string(),
+            output: String::new(),
+            rubric_version: "v1".to_string(),
+        };
+        let response = EvaluateResponse {
+            quality_score: quality,
+            delta_score: 0.0,
+            robust_pct: 100.0,
+            cache_hit: false,
+            time_ms: 1.0,
+            routing_path: "local".to_string(),
+            violations: Vec::new(),
+            stale: false,
+            cached_at: None,
+            expired: false,
+        };
+        history
+            .record_evaluation(&request, &response, id.map(str::to_string))
+            .unwrap();
+    }
+
+    #[test]
+    fn lineage_roots_cover_orphans_and_unknown_parents() {
+        let history = history();
+        mutate(&history, "g", "root", None);
+        mutate(&history, "g", "child", Some("root"));
+        mutate(&history, "g", "grandchild", Some("child"));
+        mutate(&history, "g", "orphan", Some("pruned"));
+        mutate(&history, "other", "elsewhere", None);
+        evaluate(&history, "g", Some("child"), 0.4);
+        evaluate(&history, "g", Some("child"), 0.7);
+
+        let roots = history.lineage(Some("g"), None).unwrap();
+        let ids: Vec<&str> = roots
+            .iter()
+            .map(|r| r.variant.variant_id.as_str())
+            .collect();
+        assert_eq!(ids, ["root", "orphan"]);
+
+        let child = &roots[0].children[0];
+        assert_eq!(child.variant.variant_id, "child");
+        assert_eq!((child.evaluations, child.best_quality), (2, Some(0.7)));
+        assert_eq!(child.children[0].variant.variant_id, "grandchild");
+
+        let tree = history.lineage(None, Some("grandchild")).unwrap();
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].variant.variant_id, "root");
+        assert!(history.lineage(None, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn best_variants_picks_the_top_evaluation_per_goal() {
+        let history = history();
+        mutate(&history, "a", "a1", None);
+        mutate(&history, "a", "a2", None);
+        mutate(&history, "b", "b1", None);
+        evaluate(&history, "a", Some("a1"), 0.9);
+        evaluate(&history, "a", Some("a2"), 0.6);
+        evaluate(&history, "b", Some("b1"), 0.3);
+        evaluate(&history, "b", None, 1.0);
+
+        let best = history.best_variants(None).unwrap();
+        let picked: Vec<(&str, &str, f64)> = best
+            .iter()
+            .map(|b| {
+                (
+                    b.goal.as_str(),
+                    b.variant.variant_id.as_str(),
+                    b.quality_score,
+                )
+            })
+            .collect();
+        assert_eq!(picked, [("a", "a1", 0.9), ("b", "b1", 0.3)]);
+
+        let only_b = history.best_variants(Some("b")).unwrap();
+        assert_eq!(only_b.len(), 1);
+        assert_eq!(only_b[0].variant.variant_id, "b1");
+    }
+
+    #[test]
+    fn score_trend_groups_evaluations_into_buckets() {
+        let history = history();
+        for quality in [0.2, 0.4, 0.9] {
+            evaluate(&history, "g", None, quality);
+        }
+        evaluate(&history, "other", None, 0.0);
+        history
+            .conn
+            .lock()
+            .unwrap()
+            .execute_batch(
+                "UPDATE evaluations SET created_at = 1000 WHERE id IN (1, 2);
+                 UPDATE evaluations SET created_at = 1150 WHERE id = 3;",
+            )
+            .unwrap();
+
+        let trend = history.score_trend(Some("g"), 100.0, None).unwrap();
+        assert_eq!(trend.len(), 2);
+        assert_eq!(trend[0].bucket_start, 1000.0);
+        assert_eq!(trend[0].evaluations, 2);
+        assert!((trend[0].mean_quality - 0.3).abs() < 1e-9);
+        assert_eq!(trend[0].max_quality, 0.4);
+        assert_eq!(trend[1].bucket_start, 1100.0);
+        assert_eq!(trend[1].evaluations, 1);
+
+        let recent = history.score_trend(Some("g"), 100.0, Some(1100.0)).unwrap();
+        assert_eq!(recent.len(), 1);
+    }
+}
@@ -12,13 +12,51 @@
 )]
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::Duration;
 use sysinfo::System;
 use tauri::{api::process::{Command, CommandEvent}, Manager, RunEvent, State};
 
+mod bandit_analytics;
+mod configs;
+mod contract_dispatcher;
+mod dag_analysis;
+mod dag_diff;
+mod dag_export;
+mod dag_layout;
+mod embeddings;
+mod eval_batch;
+mod eval_cache;
+mod evolution_history;
+mod message_bus;
+mod model_manager;
+mod model_scheduler;
+mod office_layout;
+mod office_workflow;
+mod ollama;
+mod rubrics;
+mod schemas;
+mod shared_memory;
+mod util;
+mod window_manager;
+mod workflow_library;
+mod workflow_runs;
+
+use bandit_analytics::BanditPolicyManager;
+use contract_dispatcher::ContractDispatcher;
+use dag_analysis::AnalyzedWorkflowDAG;
+use embeddings::EmbeddingService;
+use eval_batch::EvalBatchRunner;
+use eval_cache::EvalCache;
+use evolution_history::EvolutionHistory;
+use model_scheduler::ModelScheduler;
+use ollama::OllamaClient;
+use rubrics::RubricManager;
+use schemas::IpcError;
+use window_manager::WindowManager;
+
 // ============================================================================
 // DATA STRUCTURES (Same as original - preserving compatibility)
 // ============================================================================
@@ -53,6 +91,14 @@ struct EvaluateResponse {
     time_ms: f64,
     routing_path: String,
     violations: Vec<String>,
+    /// Served from the local cache because the backend was unreachable
+    #[serde(default)]
+    stale: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cached_at: Option<f64>,
+    /// The cached result is older than eval.yaml `CACHE.ttl_seconds`
+    #[serde(default)]
+    expired: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +159,32 @@ struct WorkflowEdge {
     label: Option<String>,
 }
 
+#[cfg(test)]
+impl WorkflowDAG {
+    /// Test fixture: nodes labelled with their ids, all at the origin
+    fn from_edges(nodes: &[&str], edges: &[(&str, &str)]) -> Self {
+        Self {
+            nodes: nodes
+                .iter()
+                .map(|id| WorkflowNode {
+                    id: id.to_string(),
+                    node_type: "agent".to_string(),
+                    label: id.to_string(),
+                    position: Position { x: 0.0, y: 0.0 },
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| WorkflowEdge {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    label: None,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct TelemetryMetrics {
     tokens_per_sec: f64,
@@ -325,12 +397,8 @@ async fn check_models(required_models: Vec<String>) -> CheckResult {
                         .map(|s| s.to_string())
                         .collect();
 
-                    let mut missing = Vec::new();
-                    for required in &required_models {
-                        if !model_names.iter().any(|m| m.contains(required)) {
-                            missing.push(required.clone());
-                        }
-                    }
+                    // Exact tag match: `qwen2.5-coder:7b` must not accept `:7b-instruct-q2`
+                    let missing = model_manager::missing_models(&model_names, &required_models);
 
                     if missing.is_empty() {
                         CheckResult {
@@ -403,14 +471,33 @@ fn create_office_window(app: &tauri::AppHandle, office_name: &str) -> Result<tau
     .build()
 }
 
-/// IPC command to open an office in a new window
+/// IPC command to open an office in a new window. Offices known to the
+/// window manager follow their instance policy; any other office gets a
+/// single window that is focused if it is already open.
 #[tauri::command]
 async fn open_office_window(
     app: tauri::AppHandle,
+    manager: State<'_, Arc<tokio::sync::RwLock<WindowManager>>>,
     office_name: String
 ) -> Result<String, String> {
     println!("[Unity] Opening office window: {}", office_name);
 
+    if let Ok(office_type) =
+        serde_json::from_value::<window_manager::OfficeType>(serde_json::json!(office_name))
+    {
+        let opened = manager.read().await.open_office(office_type, false, None, None).await?;
+        return Ok(if opened.created {
+            format!("Opened {} office window", office_name)
+        } else {
+            format!("Focused {} office window", office_name)
+        });
+    }
+
+    if let Some(window) = app.get_window(&format!("office-{}", office_name)) {
+        window.set_focus().map_err(|e| e.to_string())?;
+        return Ok(format!("Focused {} office window", office_name));
+    }
+
     match create_office_window(&app, &office_name) {
         Ok(window) => {
             window.show().map_err(|e| e.to_string())?;
@@ -495,39 +582,36 @@ async fn health_check() -> Result<String, String> {
 #[tauri::command]
 async fn evaluate(
     state: State<'_, AppState>,
+    history: State<'_, EvolutionHistory>,
+    cache: State<'_, EvalCache>,
     request: EvaluateRequest,
-) -> Result<EvaluateResponse, String> {
-    if !*state.preflight_passed.lock().unwrap() {
-        return Err("Preflight checks failed - run diagnostics first".to_string());
-    }
-
-    let client = reqwest::Client::new();
-    let url = format!("{}/evaluate", state.backend_base_url);
-
-    match client
-        .post(&url)
-        .json(&request)
-        .timeout(Duration::from_secs(60))
-        .send()
-        .await
-    {
-        Ok(response) if response.status().is_success() => response
-            .json::<EvaluateResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e)),
-        Ok(response) => Err(format!("Backend error: {}", response.status())),
-        Err(e) => Err(format!("Request failed: {}", e)),
+    variant_id: Option<String>,
+) -> Result<EvaluateResponse, IpcError> {
+    let preflight_passed = *state.preflight_passed.lock().unwrap();
+    let result = cache
+        .evaluate(&state.backend_base_url, preflight_passed, &request)
+        .await?;
+
+    if !result.stale {
+        if let Err(e) = history.record_evaluation(&request, &result, variant_id) {
+            eprintln!("[Unity] {}", e);
+        }
     }
+    Ok(result)
 }
 
 #[tauri::command]
 async fn mutate_workflow(
     state: State<'_, AppState>,
-    request: MutateRequest,
-) -> Result<MutateResponse, String> {
+    history: State<'_, EvolutionHistory>,
+    bandit: State<'_, BanditPolicyManager>,
+    mut request: MutateRequest,
+    parent_variant_id: Option<String>,
+) -> Result<MutateResponse, IpcError> {
     if !*state.preflight_passed.lock().unwrap() {
-        return Err("Preflight checks failed".to_string());
+        return Err("Preflight checks failed".to_string().into());
     }
+    bandit.resolve_arm(&history, &mut request);
 
     let client = reqwest::Client::new();
     let url = format!("{}/mutate", state.backend_base_url);
@@ -539,31 +623,37 @@ async fn mutate_workflow(
         .send()
         .await
     {
-        Ok(response) if response.status().is_success() => response
-            .json::<MutateResponse>()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e)),
-        _ => Err("Mutation request failed".to_string()),
+        Ok(response) if response.status().is_success() => {
+            let body = response
+                .json::<serde_json::Value>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            let result: MutateResponse = schemas::decode_node_outputs(body)?;
+            if let Err(e) = history.record_mutation(&request, &result, parent_variant_id) {
+                eprintln!("[Unity] {}", e);
+            }
+            Ok(result)
+        }
+        _ => Err("Mutation request failed".to_string().into()),
     }
 }
 
 #[tauri::command]
-async fn get_bandit_status(state: State<'_, AppState>) -> Result<BanditStatus, String> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/bandit/status", state.backend_base_url);
-
-    match client.get(&url).send().await {
-        Ok(response) if response.status().is_success() => response
-            .json::<BanditStatus>()
-            .await
-            .map_err(|e| format!("Failed to parse response: {}", e)),
-        _ => Err("Failed to get bandit status".to_string()),
+async fn get_bandit_status(
+    state: State<'_, AppState>,
+    history: State<'_, EvolutionHistory>,
+) -> Result<BanditStatus, String> {
+    let status = bandit_analytics::fetch_bandit_status(&state.backend_base_url).await?;
+    if let Err(e) = history.record_bandit_status(&status) {
+        eprintln!("[Unity] {}", e);
     }
+    Ok(status)
 }
 
 #[tauri::command]
 async fn create_memory_snapshot(
     state: State<'_, AppState>,
+    embeddings: State<'_, EmbeddingService>,
     title: String,
     content: String,
 ) -> Result<MemorySnapshot, String> {
@@ -575,17 +665,31 @@ async fn create_memory_snapshot(
         "content": content
     });
 
-    match client.post(&url).json(&body).send().await {
+    let snapshot = match client.post(&url).json(&body).send().await {
         Ok(response) if response.status().is_success() => response
             .json::<MemorySnapshot>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e)),
-        _ => Err("Failed to create memory snapshot".to_string()),
-    }
+            .map_err(|e| format!("Failed to parse response: {}", e))?,
+        _ => return Err("Failed to create memory snapshot".to_string()),
+    };
+
+    // Make the new snapshot searchable; the embedding model may not be pulled yet
+    let service = embeddings.inner().clone();
+    let indexed = snapshot.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = service
+            .index_snapshots(embeddings::DEFAULT_EMBEDDING_MODEL, vec![indexed])
+            .await
+        {
+            eprintln!("[Unity] Failed to index memory snapshot: {}", e);
+        }
+    });
+
+    Ok(snapshot)
 }
 
 #[tauri::command]
-async fn get_workflow_dag(state: State<'_, AppState>) -> Result<WorkflowDAG, String> {
+async fn get_workflow_dag(state: State<'_, AppState>) -> Result<AnalyzedWorkflowDAG, String> {
     let client = reqwest::Client::new();
     let url = format!("{}/workflow/dag", state.backend_base_url);
 
@@ -593,23 +697,42 @@ async fn get_workflow_dag(state: State<'_, AppState>) -> Result<WorkflowDAG, Str
         Ok(response) if response.status().is_success() => response
             .json::<WorkflowDAG>()
             .await
+            .map(|dag| {
+                // Backend positions are often missing or stacked on each other
+                if dag_layout::needs_layout(&dag) {
+                    dag_layout::layout(dag, &HashSet::new(), &Default::default()).dag
+                } else {
+                    dag
+                }
+            })
+            .map(AnalyzedWorkflowDAG::from)
             .map_err(|e| format!("Failed to parse response: {}", e)),
         _ => Err("Failed to get workflow DAG".to_string()),
     }
 }
 
 #[tauri::command]
-async fn get_telemetry_metrics(state: State<'_, AppState>) -> Result<TelemetryMetrics, String> {
+async fn get_telemetry_metrics(
+    state: State<'_, AppState>,
+    ollama: State<'_, OllamaClient>,
+) -> Result<TelemetryMetrics, String> {
     let client = reqwest::Client::new();
     let url = format!("{}/telemetry/metrics", state.backend_base_url);
 
-    match client.get(&url).send().await {
+    let mut metrics = match client.get(&url).send().await {
         Ok(response) if response.status().is_success() => response
             .json::<TelemetryMetrics>()
             .await
-            .map_err(|e| format!("Failed to parse response: {}", e)),
-        _ => Err("Failed to get telemetry metrics".to_string()),
+            .map_err(|e| format!("Failed to parse response: {}", e))?,
+        _ => return Err("Failed to get telemetry metrics".to_string()),
+    };
+
+    // Throughput measured on streams served directly by the Rust Ollama client
+    if let Some(tokens_per_sec) = ollama.recent_tokens_per_sec().await {
+        metrics.tokens_per_sec = tokens_per_sec;
     }
+
+    Ok(metrics)
 }
 
 #[tauri::command]
@@ -632,20 +755,123 @@ fn main() {
 
     tauri::Builder::default()
         .manage(app_state)
+        .manage(OllamaClient::new(ollama::OLLAMA_BASE_URL))
+        .manage(ModelScheduler::new())
         .invoke_handler(tauri::generate_handler![
             health_check,
             run_diagnostics,
             evaluate,
             mutate_workflow,
             get_bandit_status,
+            bandit_analytics::get_bandit_policy,
+            bandit_analytics::update_bandit_policy,
+            bandit_analytics::get_bandit_analytics,
             create_memory_snapshot,
             get_workflow_dag,
+            dag_analysis::analyze_workflow_dag,
+            dag_layout::layout_workflow_dag,
+            dag_export::export_workflow_dag,
+            dag_diff::diff_workflow_dags,
+            dag_diff::diff_workflow_mutation,
+            evolution_history::list_evolution_variants,
+            evolution_history::list_evolution_evaluations,
+            evolution_history::get_variant_lineage,
+            evolution_history::get_best_variants,
+            evolution_history::get_score_trend,
+            eval_cache::get_cached_evaluation,
+            eval_cache::get_eval_cache_stats,
+            eval_cache::clear_eval_cache,
+            eval_batch::evaluate_batch,
+            eval_batch::get_eval_batch,
+            eval_batch::cancel_eval_batch,
+            rubrics::list_rubrics,
+            rubrics::get_rubric,
+            rubrics::save_rubric,
+            rubrics::delete_rubric,
+            rubrics::preview_rubric_routing,
+            rubrics::push_rubric,
             get_telemetry_metrics,
             is_preflight_passed,
             open_office_window,
-            focus_window
+            focus_window,
+            ollama::ollama_chat,
+            ollama::ollama_generate,
+            ollama::cancel_ollama_stream,
+            model_manager::list_ollama_models,
+            model_manager::show_ollama_model,
+            model_manager::pull_ollama_model,
+            model_manager::delete_ollama_model,
+            model_scheduler::get_model_schedule,
+            model_scheduler::preload_models,
+            embeddings::embed_texts,
+            embeddings::index_memory_snapshots,
+            embeddings::search_memory_snapshots,
+            embeddings::clear_memory_index,
+            schemas::validate_task_contract,
+            schemas::validate_evaluation_result,
+            schemas::validate_node_io,
+            contract_dispatcher::dispatch_task_contracts,
+            contract_dispatcher::get_contract_batch,
+            contract_dispatcher::list_contract_batches,
+            contract_dispatcher::cancel_contract_batch,
+            window_manager::create_office,
+            window_manager::close_office,
+            window_manager::get_offices,
+            window_manager::send_office_message,
+            window_manager::broadcast_message,
+            window_manager::update_office_memory_consent,
+            window_manager::update_office_memory_ttl,
+            window_manager::set_office_instance_policy,
+            message_bus::post_office_message,
+            message_bus::request_office_reply,
+            message_bus::get_queued_office_messages,
+            shared_memory::get_memory_audit,
+            shared_memory::get_memory_grants,
+            shared_memory::check_memory_grant,
+            office_workflow::orchestrate_office_workflow,
+            workflow_library::list_workflow_definitions,
+            workflow_library::get_workflow_definition,
+            workflow_library::validate_workflow_definition,
+            workflow_library::import_workflow_definition,
+            workflow_library::export_workflow_definition,
+            workflow_library::run_library_workflow,
+            workflow_runs::list_workflow_runs,
+            workflow_runs::get_workflow_run,
+            workflow_runs::pause_workflow_run,
+            workflow_runs::resume_workflow_run,
+            workflow_runs::cancel_workflow_run,
+            office_layout::save_office_layout,
+            office_layout::list_office_layouts,
+            office_layout::delete_office_layout,
+            office_layout::get_previous_workspace,
+            office_layout::restore_office_layout
         ])
         .setup(|app| {
+            // Office window registry (multi-window offices + saved layouts)
+            app.manage(Arc::new(tokio::sync::RwLock::new(WindowManager::new(
+                app.handle(),
+            ))));
+
+            // Embedding cache and memory similarity index
+            let ollama = app.state::<OllamaClient>().inner().clone();
+            app.manage(EmbeddingService::new(&app.handle(), ollama));
+
+            // Local record of mutations and evaluations
+            app.manage(EvolutionHistory::new(&app.handle()));
+
+            // Bandit policy (exploration rate, enabled arms, priors)
+            app.manage(BanditPolicyManager::new(&app.handle()));
+
+            // Offline fallback for evaluations
+            app.manage(EvalCache::new(&app.handle()));
+            app.manage(EvalBatchRunner::new(app.handle()));
+
+            // Rubric versions and eval.yaml pushes
+            app.manage(RubricManager::new(&app.handle()));
+
+            // Task contract batches dispatched to the backend
+            app.manage(ContractDispatcher::new(app.handle()));
+
             println!("[Unity] Setup: Spawning sidecars...");
 
             // 1) Start Ollama server
@@ -673,12 +899,28 @@ fn main() {
                     app_handle
                         .emit_all("unity:ready", "preflight_ok")
                         .ok();
+
+                    // The backend starts with its default exploration rate
+                    let backend_base_url = app_handle.state::<AppState>().backend_base_url.clone();
+                    let bandit = app_handle.state::<BanditPolicyManager>();
+                    if let Err(e) = bandit.sync_backend(&backend_base_url).await {
+                        eprintln!("[Unity] Failed to sync bandit policy: {}", e);
+                    }
                 } else {
                     eprintln!("[Unity] Preflight complete: FAILED");
                     app_handle
                         .emit_all("unity:warn", "preflight_failed")
                         .ok();
                 }
+
+                // Offer to reopen the offices that were open last time
+                let manager = app_handle.state::<Arc<tokio::sync::RwLock<WindowManager>>>();
+                let previous = manager.read().await.previous_workspace().await;
+                if let Some(layout) = previous {
+                    app_handle
+                        .emit_all("unity:workspace_restore_available", layout)
+                        .ok();
+                }
             });
 
             Ok(())
@@ -686,6 +928,22 @@ fn main() {
         .build(tauri::generate_context!())
         .expect("Unity build failed")
         .run(|app_handle, event| {
+            // Offices destroyed during shutdown must not be recorded as closed
+            let shutting_down = match &event {
+                RunEvent::ExitRequested { .. } | RunEvent::Exit => true,
+                RunEvent::WindowEvent { label, event: tauri::WindowEvent::Destroyed, .. } => {
+                    label == "main"
+                }
+                _ => false,
+            };
+            if shutting_down {
+                if let Some(manager) = app_handle.try_state::<Arc<tokio::sync::RwLock<WindowManager>>>() {
+                    if let Ok(manager) = manager.try_read() {
+                        manager.suspend_session();
+                    }
+                }
+            }
+
             if let RunEvent::Exit = event {
                 println!("[Unity] Exiting application...");
                 let _ = app_handle.emit_all("unity:exit", "goodbye");
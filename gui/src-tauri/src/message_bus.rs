@@ -0,0 +1,388 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{oneshot, Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::util::now_secs;
+use crate::window_manager::{OfficeType, OfficeWindow, WindowManager};
+
+/// Maximum number of undelivered messages kept per closed office
+const MAX_QUEUED_PER_OFFICE: usize = 100;
+
+/// Queued messages older than this are dropped instead of delivered
+const QUEUED_MESSAGE_TTL_SECS: f64 = 3600.0;
+
+/// Who an inter-office message is addressed to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum MessageTarget {
+    Office(OfficeType),
+    Window(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageKind {
+    #[default]
+    Notify,
+    Request,
+    Reply,
+}
+
+/// Envelope for every message routed through the bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeMessage {
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub from: Option<String>,
+    pub to: MessageTarget,
+    pub topic: String,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+    #[serde(default)]
+    pub kind: MessageKind,
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    #[serde(default)]
+    pub sent_at: f64,
+}
+
+impl OfficeMessage {
+    pub fn new(to: MessageTarget, topic: &str, payload: serde_json::Value) -> Self {
+        Self {
+            id: String::new(),
+            from: None,
+            to,
+            topic: topic.to_string(),
+            payload,
+            kind: MessageKind::Notify,
+            correlation_id: None,
+            sent_at: 0.0,
+        }
+    }
+}
+
+/// Outcome of a single `send`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryReport {
+    pub message_id: String,
+    pub delivered_to: Vec<String>,
+    pub queued: bool,
+}
+
+/// A Rust-side waiter for a reply, tied to the target the request was sent to
+struct Waiter {
+    expected: MessageTarget,
+    tx: oneshot::Sender<OfficeMessage>,
+}
+
+type PendingWaiters = Arc<Mutex<HashMap<String, Waiter>>>;
+
+/// A request that has been sent and whose reply can be awaited later.
+/// Dropping it stops waiting and forgets the request.
+pub struct PendingReply {
+    pub correlation_id: String,
+    rx: oneshot::Receiver<OfficeMessage>,
+    pending: PendingWaiters,
+}
+
+impl PendingReply {
+    /// Wait for the reply, returning as soon as it arrives or failing after `timeout`
+    pub async fn wait(&mut self, timeout: Duration) -> Result<OfficeMessage, String> {
+        match tokio::time::timeout(timeout, &mut self.rx).await {
+            Ok(Ok(reply)) => Ok(reply),
+            Ok(Err(_)) => Err("Reply channel closed".to_string()),
+            Err(_) => Err(format!(
+                "Timed out after {}s waiting for reply {}",
+                timeout.as_secs(),
+                self.correlation_id
+            )),
+        }
+    }
+}
+
+impl Drop for PendingReply {
+    fn drop(&mut self) {
+        let pending = self.pending.clone();
+        let correlation_id = std::mem::take(&mut self.correlation_id);
+        if let Ok(mut waiters) = pending.try_lock() {
+            waiters.remove(&correlation_id);
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            pending.lock().await.remove(&correlation_id);
+        });
+    }
+}
+
+/// In-process broker that validates, delivers and queues inter-office messages
+#[derive(Clone)]
+pub struct MessageBus {
+    app_handle: tauri::AppHandle,
+    windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    pending: PendingWaiters,
+    queued: Arc<Mutex<HashMap<OfficeType, VecDeque<OfficeMessage>>>>,
+}
+
+impl MessageBus {
+    pub fn new(
+        app_handle: tauri::AppHandle,
+        windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    ) -> Self {
+        Self {
+            app_handle,
+            windows,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            queued: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn validate(message: &mut OfficeMessage) -> Result<(), String> {
+        if message.topic.trim().is_empty() {
+            return Err("Message topic cannot be empty".to_string());
+        }
+
+        match message.kind {
+            MessageKind::Reply if message.correlation_id.is_none() => {
+                return Err("Reply messages must carry a correlation_id".to_string());
+            }
+            MessageKind::Request if message.correlation_id.is_none() => {
+                message.correlation_id = Some(Uuid::new_v4().to_string());
+            }
+            _ => {}
+        }
+
+        if message.id.is_empty() {
+            message.id = Uuid::new_v4().to_string();
+        }
+        message.sent_at = now_secs();
+
+        Ok(())
+    }
+
+    /// Validate and deliver a message, queueing it if the target office is not open
+    pub async fn send(&self, mut message: OfficeMessage) -> Result<DeliveryReport, String> {
+        Self::validate(&mut message)?;
+
+        // Replies to requests made from Rust resolve the waiting future directly,
+        // but only when they come from the office the request was sent to
+        if message.kind == MessageKind::Reply {
+            let correlation_id = message.correlation_id.clone().unwrap_or_default();
+            let sender_office = match &message.from {
+                Some(from) => self
+                    .windows
+                    .read()
+                    .await
+                    .get(from)
+                    .map(|w| w.office_type.clone()),
+                None => None,
+            };
+
+            let mut pending = self.pending.lock().await;
+            if let Some(waiter) = pending.get(&correlation_id) {
+                let from_expected = match (&waiter.expected, &message.from) {
+                    (MessageTarget::Window(window_id), Some(from)) => window_id == from,
+                    (MessageTarget::Office(office_type), _) => {
+                        sender_office.as_ref() == Some(office_type)
+                    }
+                    _ => false,
+                };
+                if !from_expected {
+                    return Err(format!(
+                        "Reply {} did not come from the office the request was sent to",
+                        correlation_id
+                    ));
+                }
+
+                let waiter = pending.remove(&correlation_id).expect("waiter present");
+                drop(pending);
+                let message_id = message.id.clone();
+                waiter
+                    .tx
+                    .send(message)
+                    .map_err(|_| "Requester is no longer waiting for this reply".to_string())?;
+                return Ok(DeliveryReport {
+                    message_id,
+                    delivered_to: vec!["rust".to_string()],
+                    queued: false,
+                });
+            }
+        }
+
+        let recipients: Vec<String> = {
+            let windows = self.windows.read().await;
+            match &message.to {
+                MessageTarget::Window(window_id) => {
+                    if !windows.contains_key(window_id) {
+                        return Err(format!("Window not found: {}", window_id));
+                    }
+                    vec![window_id.clone()]
+                }
+                MessageTarget::Office(office_type) => windows
+                    .values()
+                    .filter(|w| w.office_type == *office_type)
+                    .map(|w| w.id.clone())
+                    .collect(),
+            }
+        };
+
+        if recipients.is_empty() {
+            if let MessageTarget::Office(office_type) = &message.to {
+                let message_id = message.id.clone();
+                self.enqueue(office_type.clone(), message).await;
+                return Ok(DeliveryReport {
+                    message_id,
+                    delivered_to: Vec::new(),
+                    queued: true,
+                });
+            }
+        }
+
+        let mut delivered_to = Vec::new();
+        for window_id in recipients {
+            if let Some(window) = self.app_handle.get_window(&window_id) {
+                window
+                    .emit("office_message", &message)
+                    .map_err(|e| format!("Failed to deliver message: {}", e))?;
+                delivered_to.push(window_id);
+            }
+        }
+
+        Ok(DeliveryReport {
+            message_id: message.id,
+            delivered_to,
+            queued: false,
+        })
+    }
+
+    /// Send a request now and return a handle for awaiting its reply
+    pub async fn send_request(&self, mut message: OfficeMessage) -> Result<PendingReply, String> {
+        message.kind = MessageKind::Request;
+        let correlation_id = message
+            .correlation_id
+            .get_or_insert_with(|| Uuid::new_v4().to_string())
+            .clone();
+
+        let (tx, rx) = oneshot::channel();
+        let waiter = Waiter {
+            expected: message.to.clone(),
+            tx,
+        };
+        self.pending
+            .lock()
+            .await
+            .insert(correlation_id.clone(), waiter);
+
+        if let Err(e) = self.send(message).await {
+            self.pending.lock().await.remove(&correlation_id);
+            return Err(e);
+        }
+
+        Ok(PendingReply {
+            correlation_id,
+            rx,
+            pending: self.pending.clone(),
+        })
+    }
+
+    /// Send a request and wait for the matching reply, up to `timeout`
+    pub async fn request(
+        &self,
+        message: OfficeMessage,
+        timeout: Duration,
+    ) -> Result<OfficeMessage, String> {
+        self.send_request(message).await?.wait(timeout).await
+    }
+
+    async fn enqueue(&self, office_type: OfficeType, message: OfficeMessage) {
+        let mut queued = self.queued.lock().await;
+        let queue = queued.entry(office_type).or_default();
+        if queue.len() >= MAX_QUEUED_PER_OFFICE {
+            queue.pop_front();
+        }
+        queue.push_back(message);
+    }
+
+    /// Deliver messages queued for an office once one of its windows is ready
+    pub async fn flush_queue(&self, office_type: &OfficeType, window_id: &str) {
+        // Leave the queue untouched until there is a window to deliver to
+        let Some(window) = self.app_handle.get_window(window_id) else {
+            return;
+        };
+
+        let Some(queue) = self.queued.lock().await.remove(office_type) else {
+            return;
+        };
+
+        let cutoff = now_secs() - QUEUED_MESSAGE_TTL_SECS;
+        let mut undelivered = VecDeque::new();
+        for message in queue.into_iter().filter(|m| m.sent_at >= cutoff) {
+            if let Err(e) = window.emit("office_message", &message) {
+                eprintln!(
+                    "[Unity] Failed to deliver queued message {}: {}",
+                    message.id, e
+                );
+                undelivered.push_back(message);
+            }
+        }
+
+        // Put failed deliveries back ahead of anything queued in the meantime
+        if !undelivered.is_empty() {
+            let mut queued = self.queued.lock().await;
+            let queue = queued.entry(office_type.clone()).or_default();
+            undelivered.extend(queue.drain(..));
+            while undelivered.len() > MAX_QUEUED_PER_OFFICE {
+                undelivered.pop_front();
+            }
+            *queue = undelivered;
+        }
+    }
+
+    /// Snapshot of all messages waiting for an office to open
+    pub async fn queued_messages(&self) -> Vec<OfficeMessage> {
+        self.queued
+            .lock()
+            .await
+            .values()
+            .flat_map(|queue| queue.iter().cloned())
+            .collect()
+    }
+}
+
+/// Tauri commands for the inter-office message bus
+/// The sender is always the calling window, whatever the webview claims
+#[tauri::command]
+pub async fn post_office_message(
+    window: tauri::Window,
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    mut message: OfficeMessage,
+) -> Result<DeliveryReport, String> {
+    message.from = Some(window.label().to_string());
+    let manager = state.read().await;
+    manager.message_bus().send(message).await
+}
+
+#[tauri::command]
+pub async fn request_office_reply(
+    window: tauri::Window,
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    mut message: OfficeMessage,
+    timeout_secs: Option<u64>,
+) -> Result<OfficeMessage, String> {
+    message.from = Some(window.label().to_string());
+    let bus = state.read().await.message_bus().clone();
+    bus.request(message, Duration::from_secs(timeout_secs.unwrap_or(30)))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_queued_office_messages(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Vec<OfficeMessage>, String> {
+    let manager = state.read().await;
+    Ok(manager.message_bus().queued_messages().await)
+}
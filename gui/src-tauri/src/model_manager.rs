@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tauri::Window;
+
+use crate::ollama::{NdjsonStream, OllamaClient};
+
+/// Timeout for quick metadata calls (`/api/tags`, `/api/show`, `/api/delete`)
+const METADATA_TIMEOUT_SECS: u64 = 10;
+
+/// An installed model as reported by `/api/tags`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub size_bytes: u64,
+    pub family: Option<String>,
+    pub parameter_size: Option<String>,
+    pub quantization: Option<String>,
+    pub modified_at: Option<String>,
+    pub digest: Option<String>,
+}
+
+impl ModelInfo {
+    fn from_tag(tag: &Value) -> Option<Self> {
+        let name = tag["name"].as_str().or_else(|| tag["model"].as_str())?;
+        let details = &tag["details"];
+        let text = |v: &Value| v.as_str().map(str::to_string);
+
+        Some(Self {
+            name: name.to_string(),
+            size_bytes: tag["size"].as_u64().unwrap_or(0),
+            family: text(&details["family"]),
+            parameter_size: text(&details["parameter_size"]),
+            quantization: text(&details["quantization_level"]),
+            modified_at: text(&tag["modified_at"]),
+            digest: text(&tag["digest"]),
+        })
+    }
+}
+
+/// Output of `/api/show` for one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDetails {
+    pub name: String,
+    #[serde(default)]
+    pub modelfile: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<String>,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub details: Value,
+    #[serde(default)]
+    pub model_info: Value,
+}
+
+/// Payload of `ollama:pull_progress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullProgress {
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    pub percent: Option<f64>,
+}
+
+/// Normalize a model reference to `name:tag`, defaulting the tag to `latest`
+pub fn normalize_tag(model: &str) -> String {
+    let model = model.trim();
+    let model = model
+        .strip_prefix("registry.ollama.ai/library/")
+        .unwrap_or(model);
+    let name_part = model.rsplit('/').next().unwrap_or(model);
+    if name_part.contains(':') {
+        model.to_string()
+    } else {
+        format!("{}:latest", model)
+    }
+}
+
+/// Whether an installed model is exactly the required one (`llama3` == `llama3:latest`)
+pub fn tag_matches(installed: &str, required: &str) -> bool {
+    normalize_tag(installed) == normalize_tag(required)
+}
+
+/// Required models that are not installed, compared by exact tag
+pub fn missing_models(installed: &[String], required: &[String]) -> Vec<String> {
+    required
+        .iter()
+        .filter(|r| !installed.iter().any(|m| tag_matches(m, r)))
+        .cloned()
+        .collect()
+}
+
+fn metadata_timeout() -> Duration {
+    Duration::from_secs(METADATA_TIMEOUT_SECS)
+}
+
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let detail = response.text().await.unwrap_or_default();
+    Err(format!("Ollama returned {}: {}", status, detail.trim()))
+}
+
+/// Installed models with size, family, quantization and modification date
+pub async fn list_models(client: &OllamaClient) -> Result<Vec<ModelInfo>, String> {
+    let response = client
+        .http()
+        .get(client.url("/api/tags"))
+        .timeout(metadata_timeout())
+        .send()
+        .await
+        .map_err(|e| format!("Ollama not reachable: {}", e))?;
+
+    let data: Value = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Ollama models list: {}", e))?;
+
+    let mut models: Vec<ModelInfo> = data["models"]
+        .as_array()
+        .map(|tags| tags.iter().filter_map(ModelInfo::from_tag).collect())
+        .unwrap_or_default();
+    models.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(models)
+}
+
+pub async fn show_model(client: &OllamaClient, name: &str) -> Result<ModelDetails, String> {
+    let response = client
+        .http()
+        .post(client.url("/api/show"))
+        .json(&json!({ "model": name, "name": name }))
+        .timeout(metadata_timeout())
+        .send()
+        .await
+        .map_err(|e| format!("Ollama not reachable: {}", e))?;
+
+    let mut data: Value = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse model details: {}", e))?;
+    data["name"] = Value::String(name.to_string());
+
+    serde_json::from_value(data).map_err(|e| format!("Failed to parse model details: {}", e))
+}
+
+pub async fn delete_model(client: &OllamaClient, name: &str) -> Result<(), String> {
+    let response = client
+        .http()
+        .delete(client.url("/api/delete"))
+        .json(&json!({ "model": name, "name": name }))
+        .timeout(metadata_timeout())
+        .send()
+        .await
+        .map_err(|e| format!("Ollama not reachable: {}", e))?;
+
+    check_status(response).await.map(|_| ())
+}
+
+/// Pull a model, forwarding each progress update to `window`
+pub async fn pull_model(client: &OllamaClient, window: &Window, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Model name cannot be empty".to_string());
+    }
+
+    let response = client
+        .http()
+        .post(client.url("/api/pull"))
+        .json(&json!({ "model": name, "name": name, "stream": true }))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama not reachable: {}", e))?;
+    let mut lines = NdjsonStream::new(response).await?;
+
+    let mut succeeded = false;
+    while let Some(value) = lines.next().await? {
+        let completed = value["completed"].as_u64();
+        let total = value["total"].as_u64();
+        let progress = PullProgress {
+            model: name.to_string(),
+            status: value["status"].as_str().unwrap_or_default().to_string(),
+            digest: value["digest"].as_str().map(str::to_string),
+            completed,
+            total,
+            percent: match (completed, total) {
+                (Some(done), Some(total)) if total > 0 => Some(done as f64 / total as f64 * 100.0),
+                _ => None,
+            },
+        };
+
+        succeeded = progress.status == "success";
+        window.emit("ollama:pull_progress", &progress).ok();
+    }
+
+    if succeeded {
+        Ok(())
+    } else {
+        Err(format!("Pull of {} ended without success", name))
+    }
+}
+
+/// Tauri commands for managing Ollama models
+#[tauri::command]
+pub async fn list_ollama_models(
+    client: tauri::State<'_, OllamaClient>,
+) -> Result<Vec<ModelInfo>, String> {
+    list_models(&client).await
+}
+
+#[tauri::command]
+pub async fn show_ollama_model(
+    client: tauri::State<'_, OllamaClient>,
+    name: String,
+) -> Result<ModelDetails, String> {
+    show_model(&client, &name).await
+}
+
+#[tauri::command]
+pub async fn pull_ollama_model(
+    window: Window,
+    client: tauri::State<'_, OllamaClient>,
+    name: String,
+) -> Result<(), String> {
+    pull_model(&client, &window, &name).await
+}
+
+#[tauri::command]
+pub async fn delete_ollama_model(
+    client: tauri::State<'_, OllamaClient>,
+    name: String,
+) -> Result<(), String> {
+    delete_model(&client, &name).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(models: &[&str]) -> Vec<String> {
+        models.iter().map(|m| m.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_must_match_exactly() {
+        assert!(!tag_matches(
+            "qwen2.5-coder:7b-instruct-q2",
+            "qwen2.5-coder:7b"
+        ));
+        assert!(!tag_matches(
+            "qwen2.5-coder:7b",
+            "qwen2.5-coder:7b-instruct-q2"
+        ));
+        assert!(tag_matches("qwen2.5-coder:7b", " qwen2.5-coder:7b "));
+    }
+
+    #[test]
+    fn untagged_names_default_to_latest() {
+        assert_eq!(normalize_tag("llama3"), "llama3:latest");
+        assert!(tag_matches("llama3:latest", "llama3"));
+        assert!(!tag_matches("llama3:8b", "llama3"));
+    }
+
+    #[test]
+    fn registry_prefixes_are_handled() {
+        assert_eq!(
+            normalize_tag("registry.ollama.ai/library/llama3"),
+            "llama3:latest"
+        );
+        assert!(tag_matches(
+            "registry.ollama.ai/library/llama3:8b",
+            "llama3:8b"
+        ));
+        // A port in the host is not a tag
+        assert_eq!(
+            normalize_tag("localhost:5000/team/model"),
+            "localhost:5000/team/model:latest"
+        );
+        assert!(!tag_matches("team/model", "model"));
+    }
+
+    #[test]
+    fn missing_models_keeps_the_required_spelling() {
+        let installed = names(&["qwen2.5-coder:7b-instruct-q2", "nomic-embed-text:latest"]);
+        let required = names(&["qwen2.5-coder:7b", "nomic-embed-text"]);
+
+        assert_eq!(missing_models(&installed, &required), ["qwen2.5-coder:7b"]);
+        assert!(missing_models(&installed, &[]).is_empty());
+    }
+}
@@ -0,0 +1,582 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+use sysinfo::System;
+use tauri::Window;
+use tokio::sync::Mutex;
+
+use crate::configs::BudgetConfig;
+use crate::model_manager::{list_models, tag_matches, ModelInfo};
+use crate::ollama::OllamaClient;
+use crate::AppState;
+
+const BYTES_PER_GB: f64 = 1_073_741_824.0;
+
+/// RAM left for the OS and the app itself after loading models
+const SYSTEM_RESERVE_GB: f64 = 2.0;
+
+/// Runtime overhead over the on-disk size when budget.yaml has no estimate
+const DISK_SIZE_RAM_FACTOR: f64 = 1.2;
+
+/// How long `admit` reuses budget.yaml and Ollama's model lists
+const SNAPSHOT_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Fits with the system reserve intact (or is already resident)
+    Allow,
+    /// Fits, but eats into the reserve or has no RAM estimate
+    Warn,
+    /// Would exceed free RAM
+    Refuse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDecision {
+    pub model: String,
+    pub estimated_ram_gb: Option<f64>,
+    pub resident: bool,
+    pub verdict: Verdict,
+    pub reason: String,
+}
+
+/// Which models can be loaded right now, given free RAM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSchedule {
+    pub total_ram_gb: f64,
+    pub available_ram_gb: f64,
+    pub reserve_gb: f64,
+    pub resident: Vec<String>,
+    pub decisions: Vec<ModelDecision>,
+    /// The backend's `/models/status`, when it is reachable
+    pub backend_status: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreloadReport {
+    pub schedule: ModelSchedule,
+    pub loaded: Vec<String>,
+    pub skipped: Vec<String>,
+    /// The backend's `/models/preload` result, when it did the preloading
+    pub backend: Option<Value>,
+}
+
+/// Models currently loaded by Ollama (`/api/ps`)
+async fn resident_models(client: &OllamaClient) -> Vec<String> {
+    let response = client
+        .http()
+        .get(client.url("/api/ps"))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await;
+
+    let Ok(response) = response else {
+        return Vec::new();
+    };
+    let data: Value = response.json().await.unwrap_or_default();
+    data["models"]
+        .as_array()
+        .map(|models| {
+            models
+                .iter()
+                .filter_map(|m| m["name"].as_str().or_else(|| m["model"].as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn configured_ram_gb(budget: &BudgetConfig, model: &str) -> Option<f64> {
+    budget
+        .model_limits
+        .iter()
+        .find(|(name, _)| tag_matches(name, model))
+        .and_then(|(_, limit)| limit.estimated_ram_gb)
+}
+
+/// Whether any of `models` needs `/api/tags` for its RAM estimate
+fn needs_installed(budget: &BudgetConfig, models: &[String]) -> bool {
+    models
+        .iter()
+        .any(|m| configured_ram_gb(budget, m).is_none())
+}
+
+/// RAM estimate from budget.yaml, falling back to the installed model's size
+fn estimate_ram_gb(budget: &BudgetConfig, installed: &[ModelInfo], model: &str) -> Option<f64> {
+    configured_ram_gb(budget, model).or_else(|| {
+        installed
+            .iter()
+            .find(|m| tag_matches(&m.name, model))
+            .map(|m| m.size_bytes as f64 / BYTES_PER_GB * DISK_SIZE_RAM_FACTOR)
+    })
+}
+
+/// Total and currently available RAM
+#[derive(Debug, Clone, Copy)]
+struct RamSnapshot {
+    total_gb: f64,
+    available_gb: f64,
+}
+
+impl RamSnapshot {
+    fn current() -> Self {
+        let mut sys = System::new();
+        sys.refresh_memory();
+        Self {
+            total_gb: sys.total_memory() as f64 / BYTES_PER_GB,
+            available_gb: sys.available_memory() as f64 / BYTES_PER_GB,
+        }
+    }
+}
+
+/// Decide, in order, which of `models` can be loaded alongside each other
+pub async fn plan(
+    client: &OllamaClient,
+    budget: &BudgetConfig,
+    models: &[String],
+) -> ModelSchedule {
+    let resident = resident_models(client).await;
+    let installed = if needs_installed(budget, models) {
+        list_models(client).await.unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    plan_with(budget, RamSnapshot::current(), resident, &installed, models)
+}
+
+/// `plan` over already-fetched memory figures and model lists. Resident
+/// models already count against available RAM; budget.yaml `max_concurrency`
+/// caps how many models may be loaded at once.
+fn plan_with(
+    budget: &BudgetConfig,
+    ram: RamSnapshot,
+    resident: Vec<String>,
+    installed: &[ModelInfo],
+    models: &[String],
+) -> ModelSchedule {
+    let mut remaining = ram.available_gb;
+    let mut loaded = resident.len();
+    let mut decisions = Vec::with_capacity(models.len());
+
+    for model in models {
+        let estimated_ram_gb = estimate_ram_gb(budget, installed, model);
+        let is_resident = resident.iter().any(|r| tag_matches(r, model));
+
+        let (verdict, reason) = match estimated_ram_gb {
+            _ if is_resident => (Verdict::Allow, "Already loaded".to_string()),
+            _ if budget.max_concurrency.is_some_and(|max| loaded >= max) => (
+                Verdict::Refuse,
+                format!(
+                    "{} models are loaded and budget.yaml max_concurrency is {}",
+                    loaded,
+                    budget.max_concurrency.unwrap_or_default()
+                ),
+            ),
+            None => (
+                Verdict::Warn,
+                "No RAM estimate in budget.yaml and model is not installed".to_string(),
+            ),
+            Some(needed) if needed > remaining => (
+                Verdict::Refuse,
+                format!(
+                    "Needs ~{:.1} GB but only {:.1} GB is free",
+                    needed, remaining
+                ),
+            ),
+            Some(needed) if remaining - needed < SYSTEM_RESERVE_GB => (
+                Verdict::Warn,
+                format!(
+                    "Leaves {:.1} GB free, below the {:.1} GB reserve",
+                    remaining - needed,
+                    SYSTEM_RESERVE_GB
+                ),
+            ),
+            Some(needed) => (
+                Verdict::Allow,
+                format!("Needs ~{:.1} GB of {:.1} GB free", needed, remaining),
+            ),
+        };
+
+        if !is_resident && verdict != Verdict::Refuse {
+            remaining -= estimated_ram_gb.unwrap_or(0.0);
+            loaded += 1;
+        }
+
+        decisions.push(ModelDecision {
+            model: model.clone(),
+            estimated_ram_gb,
+            resident: is_resident,
+            verdict,
+            reason,
+        });
+    }
+
+    ModelSchedule {
+        total_ram_gb: ram.total_gb,
+        available_ram_gb: ram.available_gb,
+        reserve_gb: SYSTEM_RESERVE_GB,
+        resident,
+        decisions,
+        backend_status: None,
+    }
+}
+
+/// A value fetched from disk or Ollama, reused until it is `SNAPSHOT_TTL` old
+struct Cached<T> {
+    value: T,
+    fetched_at: Instant,
+}
+
+impl<T: Clone> Cached<T> {
+    fn fresh(slot: &Option<Self>) -> Option<T> {
+        slot.as_ref()
+            .filter(|c| c.fetched_at.elapsed() < SNAPSHOT_TTL)
+            .map(|c| c.value.clone())
+    }
+
+    fn store(slot: &mut Option<Self>, value: T) -> T {
+        *slot = Some(Self {
+            value: value.clone(),
+            fetched_at: Instant::now(),
+        });
+        value
+    }
+}
+
+#[derive(Default)]
+struct Snapshots {
+    budget: Option<Cached<BudgetConfig>>,
+    resident: Option<Cached<Vec<String>>>,
+    installed: Option<Cached<Vec<ModelInfo>>>,
+}
+
+/// Admission control for chat/generate requests. budget.yaml and Ollama's
+/// model lists are cached briefly so interactive chats do not pay extra
+/// round-trips before every request.
+#[derive(Default)]
+pub struct ModelScheduler {
+    snapshots: Mutex<Snapshots>,
+}
+
+impl ModelScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gate a chat/generate request: refuse if the model cannot fit, warn the
+    /// requesting window if it only barely fits. A missing budget.yaml falls
+    /// back to installed model sizes rather than blocking chat.
+    pub async fn admit(
+        &self,
+        client: &OllamaClient,
+        window: &Window,
+        model: &str,
+    ) -> Result<(), String> {
+        let models = [model.to_string()];
+        let mut snapshots = self.snapshots.lock().await;
+
+        let budget = match Cached::fresh(&snapshots.budget) {
+            Some(budget) => budget,
+            None => Cached::store(
+                &mut snapshots.budget,
+                BudgetConfig::load().unwrap_or_default(),
+            ),
+        };
+        let resident = match Cached::fresh(&snapshots.resident) {
+            Some(resident) => resident,
+            None => Cached::store(&mut snapshots.resident, resident_models(client).await),
+        };
+        let installed = match Cached::fresh(&snapshots.installed) {
+            Some(installed) => installed,
+            None if needs_installed(&budget, &models) => Cached::store(
+                &mut snapshots.installed,
+                list_models(client).await.unwrap_or_default(),
+            ),
+            None => Vec::new(),
+        };
+
+        let schedule = plan_with(
+            &budget,
+            RamSnapshot::current(),
+            resident,
+            &installed,
+            &models,
+        );
+        let Some(decision) = schedule.decisions.into_iter().next() else {
+            return Ok(());
+        };
+
+        // The request is about to load the model; count it as resident so the
+        // next admission does not budget for it again from a stale `/api/ps`
+        if decision.verdict != Verdict::Refuse && !decision.resident {
+            if let Some(cached) = snapshots.resident.as_mut() {
+                cached.value.push(model.to_string());
+            }
+        }
+        drop(snapshots);
+
+        match decision.verdict {
+            Verdict::Allow => Ok(()),
+            Verdict::Warn => {
+                window.emit("ollama:memory_warning", &decision).ok();
+                Ok(())
+            }
+            Verdict::Refuse => Err(format!("Cannot load {}: {}", model, decision.reason)),
+        }
+    }
+}
+
+/// Models scheduled by default: everything budget.yaml has limits for
+fn default_models(budget: &BudgetConfig) -> Vec<String> {
+    let mut models: Vec<String> = budget.model_limits.keys().cloned().collect();
+    models.sort();
+    models
+}
+
+/// Split the backend's per-model preload results (`models.<name>.status`)
+/// into loaded and failed models
+fn backend_outcome(backend: &Value) -> (Vec<String>, Vec<String>) {
+    let mut loaded = Vec::new();
+    let mut skipped = Vec::new();
+    if let Some(models) = backend["models"].as_object() {
+        for (model, result) in models {
+            if result["status"] == "loaded" {
+                loaded.push(model.clone());
+            } else {
+                skipped.push(model.clone());
+            }
+        }
+    }
+    (loaded, skipped)
+}
+
+async fn backend_json(request: reqwest::RequestBuilder) -> Option<Value> {
+    let response = request.timeout(Duration::from_secs(5)).send().await.ok()?;
+    response.json().await.ok()
+}
+
+/// Tauri commands for RAM-aware model scheduling
+#[tauri::command]
+pub async fn get_model_schedule(
+    state: tauri::State<'_, AppState>,
+    client: tauri::State<'_, OllamaClient>,
+    models: Option<Vec<String>>,
+) -> Result<ModelSchedule, String> {
+    let budget = BudgetConfig::load()?;
+    let models = models.unwrap_or_else(|| default_models(&budget));
+
+    let mut schedule = plan(&client, &budget, &models).await;
+    schedule.backend_status = backend_json(
+        client
+            .http()
+            .get(format!("{}/models/status", state.backend_base_url)),
+    )
+    .await;
+    Ok(schedule)
+}
+
+/// Preload models that fit. With no list, the backend's preloader loads the
+/// budget.yaml models, but only if all of them fit (or `force` is set).
+#[tauri::command]
+pub async fn preload_models(
+    state: tauri::State<'_, AppState>,
+    client: tauri::State<'_, OllamaClient>,
+    models: Option<Vec<String>>,
+    force: Option<bool>,
+) -> Result<PreloadReport, String> {
+    let force = force.unwrap_or(false);
+    let use_backend = models.is_none();
+    let budget = BudgetConfig::load()?;
+    let models = models.unwrap_or_else(|| default_models(&budget));
+
+    let schedule = plan(&client, &budget, &models).await;
+    let refused: Vec<&ModelDecision> = schedule
+        .decisions
+        .iter()
+        .filter(|d| d.verdict == Verdict::Refuse)
+        .collect();
+
+    if use_backend {
+        if !refused.is_empty() && !force {
+            let reasons: Vec<String> = refused
+                .iter()
+                .map(|d| format!("{}: {}", d.model, d.reason))
+                .collect();
+            return Err(format!("Refusing to preload: {}", reasons.join("; ")));
+        }
+
+        let backend = client
+            .http()
+            .post(format!("{}/models/preload", state.backend_base_url))
+            .timeout(Duration::from_secs(300))
+            .send()
+            .await
+            .map_err(|e| format!("Backend not reachable: {}", e))?
+            .json::<Value>()
+            .await
+            .map_err(|e| format!("Failed to parse preload result: {}", e))?;
+        if let Some(error) = backend["error"].as_str() {
+            return Err(format!("Backend preload failed: {}", error));
+        }
+
+        let (loaded, skipped) = backend_outcome(&backend);
+        return Ok(PreloadReport {
+            loaded,
+            skipped,
+            backend: Some(backend),
+            schedule,
+        });
+    }
+
+    let mut loaded = Vec::new();
+    let mut skipped = Vec::new();
+    for decision in &schedule.decisions {
+        if decision.resident || (decision.verdict == Verdict::Refuse && !force) {
+            skipped.push(decision.model.clone());
+            continue;
+        }
+
+        // An empty prompt makes Ollama load the model without generating
+        client
+            .http()
+            .post(client.url("/api/generate"))
+            .json(&json!({ "model": decision.model, "prompt": "", "stream": false }))
+            .timeout(Duration::from_secs(300))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to preload {}: {}", decision.model, e))?
+            .error_for_status()
+            .map_err(|e| format!("Failed to preload {}: {}", decision.model, e))?;
+        loaded.push(decision.model.clone());
+    }
+
+    Ok(PreloadReport {
+        schedule,
+        loaded,
+        skipped,
+        backend: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RAM: RamSnapshot = RamSnapshot {
+        total_gb: 32.0,
+        available_gb: 20.0,
+    };
+
+    fn budget(max_concurrency: Option<usize>) -> BudgetConfig {
+        let mut budget: BudgetConfig = serde_yaml::from_str(
+            "MODEL_LIMITS:\n  small:7b: {estimated_ram_gb: 8}\n  large:14b: {estimated_ram_gb: 16}\n  huge:70b: {estimated_ram_gb: 40}\n",
+        )
+        .unwrap();
+        budget.max_concurrency = max_concurrency;
+        budget
+    }
+
+    fn models(names: &[&str]) -> Vec<String> {
+        names.iter().map(|m| m.to_string()).collect()
+    }
+
+    fn verdicts(schedule: &ModelSchedule) -> Vec<Verdict> {
+        schedule.decisions.iter().map(|d| d.verdict).collect()
+    }
+
+    #[test]
+    fn admits_warns_and_refuses_by_free_ram() {
+        let schedule = plan_with(
+            &budget(None),
+            RAM,
+            Vec::new(),
+            &[],
+            &models(&["small:7b", "large:14b", "huge:70b", "unknown"]),
+        );
+
+        // 20 GB free: small leaves 12, large would leave -4, huge never fits
+        assert_eq!(
+            verdicts(&schedule),
+            [
+                Verdict::Allow,
+                Verdict::Refuse,
+                Verdict::Refuse,
+                Verdict::Warn
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_when_a_model_eats_into_the_reserve() {
+        let ram = RamSnapshot {
+            total_gb: 16.0,
+            available_gb: 9.0,
+        };
+        let schedule = plan_with(&budget(None), ram, Vec::new(), &[], &models(&["small:7b"]));
+
+        assert_eq!(verdicts(&schedule), [Verdict::Warn]);
+    }
+
+    #[test]
+    fn installed_size_stands_in_for_a_missing_estimate() {
+        let installed = [ModelInfo {
+            name: "unknown:latest".to_string(),
+            size_bytes: 5 * BYTES_PER_GB as u64,
+            family: None,
+            parameter_size: None,
+            quantization: None,
+            modified_at: None,
+            digest: None,
+        }];
+        let schedule = plan_with(
+            &budget(None),
+            RAM,
+            Vec::new(),
+            &installed,
+            &models(&["unknown"]),
+        );
+
+        assert_eq!(schedule.decisions[0].estimated_ram_gb, Some(6.0));
+        assert_eq!(verdicts(&schedule), [Verdict::Allow]);
+    }
+
+    #[test]
+    fn resident_models_are_allowed_without_reserving_ram_again() {
+        let ram = RamSnapshot {
+            total_gb: 32.0,
+            available_gb: 12.0,
+        };
+        let schedule = plan_with(
+            &budget(None),
+            ram,
+            models(&["large:14b"]),
+            &[],
+            &models(&["large:14b", "small:7b"]),
+        );
+
+        // large's 16 GB is already out of the 12 GB free, so small still fits
+        assert!(schedule.decisions[0].resident);
+        assert_eq!(verdicts(&schedule), [Verdict::Allow, Verdict::Allow]);
+    }
+
+    #[test]
+    fn max_concurrency_caps_loaded_models() {
+        let schedule = plan_with(
+            &budget(Some(2)),
+            RamSnapshot {
+                total_gb: 64.0,
+                available_gb: 60.0,
+            },
+            models(&["large:14b"]),
+            &[],
+            &models(&["small:7b", "large:14b", "huge:70b"]),
+        );
+
+        assert_eq!(
+            verdicts(&schedule),
+            [Verdict::Allow, Verdict::Allow, Verdict::Refuse]
+        );
+        assert!(schedule.decisions[2].reason.contains("max_concurrency"));
+    }
+}
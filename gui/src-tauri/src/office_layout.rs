@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::Window;
+use tokio::sync::RwLock;
+
+use crate::util::{now_secs, write_atomic};
+use crate::window_manager::{OfficeType, OfficeWindow, WindowManager};
+
+/// File (inside the app data dir) holding the last session and named layouts
+pub const LAYOUT_FILE: &str = "office_layouts.json";
+
+/// Delay before a burst of move/resize events is written to disk
+const SESSION_SAVE_DEBOUNCE_MS: u64 = 750;
+
+/// Geometry and memory settings of one office window within a layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeLayoutEntry {
+    pub office_type: OfficeType,
+    pub position: Option<(i32, i32)>,
+    pub size: (u32, u32),
+    pub monitor: Option<String>,
+    pub memory_consent: bool,
+    pub shared_memory_ttl: u64,
+}
+
+impl From<&OfficeWindow> for OfficeLayoutEntry {
+    fn from(window: &OfficeWindow) -> Self {
+        Self {
+            office_type: window.office_type.clone(),
+            position: window.position,
+            size: window.size,
+            monitor: window.monitor.clone(),
+            memory_consent: window.memory_consent,
+            shared_memory_ttl: window.shared_memory_ttl,
+        }
+    }
+}
+
+/// A snapshot of open office windows ("Trading desk", "Legal review", ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeLayout {
+    pub name: String,
+    pub saved_at: f64,
+    pub offices: Vec<OfficeLayoutEntry>,
+}
+
+impl OfficeLayout {
+    fn new(name: &str, offices: Vec<OfficeLayoutEntry>) -> Self {
+        Self {
+            name: name.to_string(),
+            saved_at: now_secs(),
+            offices,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LayoutFile {
+    last_session: Option<OfficeLayout>,
+    named: BTreeMap<String, OfficeLayout>,
+}
+
+/// On-disk store for the previous workspace and user-named layouts
+pub struct LayoutStore {
+    path: Option<PathBuf>,
+    data: LayoutFile,
+}
+
+impl LayoutStore {
+    /// Load the store from `path`, starting empty if the file is missing or unreadable
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let data = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| match serde_json::from_str::<LayoutFile>(&raw) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    eprintln!("[Unity] Ignoring corrupt office layout file: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        Self { path, data }
+    }
+
+    fn persist(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Err("No app data directory available for office layouts".to_string());
+        };
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create layout directory: {}", e))?;
+        }
+
+        let json = serde_json::to_string_pretty(&self.data)
+            .map_err(|e| format!("Failed to serialize layouts: {}", e))?;
+
+        write_atomic(path, json).map_err(|e| format!("Failed to write layouts: {}", e))
+    }
+
+    /// Record the currently open offices as the workspace to offer on next launch
+    pub fn record_session(&mut self, offices: Vec<OfficeLayoutEntry>) -> Result<(), String> {
+        self.data.last_session = Some(OfficeLayout::new("Previous session", offices));
+        self.persist()
+    }
+
+    pub fn last_session(&self) -> Option<&OfficeLayout> {
+        self.data
+            .last_session
+            .as_ref()
+            .filter(|layout| !layout.offices.is_empty())
+    }
+
+    pub fn save_named(
+        &mut self,
+        name: &str,
+        offices: Vec<OfficeLayoutEntry>,
+    ) -> Result<OfficeLayout, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Layout name cannot be empty".to_string());
+        }
+
+        let layout = OfficeLayout::new(name, offices);
+        self.data.named.insert(name.to_string(), layout.clone());
+        self.persist()?;
+        Ok(layout)
+    }
+
+    pub fn get_named(&self, name: &str) -> Option<&OfficeLayout> {
+        self.data.named.get(name)
+    }
+
+    pub fn list_named(&self) -> Vec<OfficeLayout> {
+        self.data.named.values().cloned().collect()
+    }
+
+    pub fn delete_named(&mut self, name: &str) -> Result<(), String> {
+        if self.data.named.remove(name).is_none() {
+            return Err(format!("Layout not found: {}", name));
+        }
+        self.persist()
+    }
+}
+
+/// Debounced writer that snapshots the window registry as the last session
+#[derive(Clone)]
+pub struct SessionRecorder {
+    windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    layouts: Arc<RwLock<LayoutStore>>,
+    generation: Arc<AtomicU64>,
+    suspended: Arc<AtomicBool>,
+}
+
+impl SessionRecorder {
+    pub fn new(
+        windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+        layouts: Arc<RwLock<LayoutStore>>,
+    ) -> Self {
+        Self {
+            windows,
+            layouts,
+            generation: Arc::new(AtomicU64::new(0)),
+            suspended: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Stop recording (and drop any pending save) so that windows torn down
+    /// while the app exits do not overwrite the last session with an empty one
+    pub fn suspend(&self) {
+        self.suspended.store(true, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Schedule a session save; only the last call within the debounce window writes
+    pub fn schedule(&self) {
+        if self.suspended.load(Ordering::SeqCst) {
+            return;
+        }
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let recorder = self.clone();
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(tokio::time::Duration::from_millis(SESSION_SAVE_DEBOUNCE_MS)).await;
+            if recorder.suspended.load(Ordering::SeqCst)
+                || recorder.generation.load(Ordering::SeqCst) != generation
+            {
+                return;
+            }
+
+            let offices: Vec<OfficeLayoutEntry> = recorder
+                .windows
+                .read()
+                .await
+                .values()
+                .map(OfficeLayoutEntry::from)
+                .collect();
+
+            if let Err(e) = recorder.layouts.write().await.record_session(offices) {
+                eprintln!("[Unity] Failed to record office session: {}", e);
+            }
+        });
+    }
+}
+
+/// Logical position/size of a window and the monitor it sits on
+pub struct WindowGeometry {
+    pub position: (i32, i32),
+    pub size: (u32, u32),
+    pub monitor: Option<String>,
+}
+
+/// Read the current geometry of a window, if the platform reports it
+pub fn read_window_geometry(window: &Window) -> Option<WindowGeometry> {
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<i32>(scale);
+    let size = window.inner_size().ok()?.to_logical::<u32>(scale);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    Some(WindowGeometry {
+        position: (position.x, position.y),
+        size: (size.width, size.height),
+        monitor,
+    })
+}
+
+/// Tauri commands for office layouts
+#[tauri::command]
+pub async fn save_office_layout(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    name: String,
+) -> Result<OfficeLayout, String> {
+    let manager = state.read().await;
+    manager.save_layout(&name).await
+}
+
+#[tauri::command]
+pub async fn list_office_layouts(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Vec<OfficeLayout>, String> {
+    let manager = state.read().await;
+    Ok(manager.list_layouts().await)
+}
+
+#[tauri::command]
+pub async fn delete_office_layout(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    name: String,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.delete_layout(&name).await
+}
+
+#[tauri::command]
+pub async fn get_previous_workspace(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Option<OfficeLayout>, String> {
+    let manager = state.read().await;
+    Ok(manager.previous_workspace().await)
+}
+
+/// Restore a named layout, or the previous session when `name` is omitted
+#[tauri::command]
+pub async fn restore_office_layout(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    name: Option<String>,
+) -> Result<Vec<String>, String> {
+    let manager = state.read().await;
+    manager.restore_layout(name.as_deref()).await
+}
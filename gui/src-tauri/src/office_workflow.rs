@@ -0,0 +1,861 @@
+use futures_util::future::{join_all, BoxFuture};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{watch, RwLock};
+
+use crate::message_bus::{MessageTarget, OfficeMessage, PendingReply};
+use crate::window_manager::{OfficeType, WindowManager};
+use crate::workflow_runs::{self, RunControl, WorkflowRuns, CANCELLED};
+
+/// Topic used for messages sent by workflow steps
+const WORKFLOW_TOPIC: &str = "workflow_step";
+
+/// Variable that always holds the payload of the most recent reply
+const LAST_REPLY_VARIABLE: &str = "last_reply";
+
+/// Variable holding the error message seen by a step's error handler
+const LAST_ERROR_VARIABLE: &str = "last_error";
+
+/// Workflow definition for multi-office orchestration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowDefinition {
+    pub name: String,
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowStep {
+    /// Optional stable identifier, used in error messages
+    #[serde(default)]
+    pub id: Option<String>,
+    pub action: WorkflowAction,
+    #[serde(default)]
+    pub description: String,
+    /// Variable that receives this step's output
+    #[serde(default)]
+    pub bind: Option<String>,
+    #[serde(default)]
+    pub on_error: OnError,
+}
+
+impl WorkflowStep {
+    fn label(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.description)
+    }
+}
+
+/// Step actions. String values inside message payloads may reference
+/// variables as `{{name}}` or `{{name.field.0}}`.
+///
+/// Step outputs (bound via `WorkflowStep::bind`):
+/// - `OpenOffice`: the window id (new or reused)
+/// - `SendMessage`: the request's correlation id
+/// - `WaitForResponse` / `Ask`: the reply payload
+/// - `Parallel`: an array with each branch's last reply
+/// - `SetVariable`: the rendered value
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum WorkflowAction {
+    OpenOffice(OpenOfficeSpec),
+    SendMessage(OfficeType, Value),
+    /// Wait for the reply to the most recent `SendMessage`; a bare number
+    /// is still accepted as `timeout_secs`
+    #[serde(deserialize_with = "deserialize_wait")]
+    WaitForResponse {
+        timeout_secs: u64,
+        on_timeout: TimeoutPolicy,
+    },
+    /// `SendMessage` followed by `WaitForResponse`
+    Ask {
+        office: OfficeType,
+        message: Value,
+        timeout_secs: u64,
+        #[serde(default)]
+        on_timeout: TimeoutPolicy,
+    },
+    SetVariable {
+        name: String,
+        value: Value,
+    },
+    /// Run `then` if the condition holds, otherwise `otherwise`
+    Branch {
+        condition: Condition,
+        then: Vec<WorkflowStep>,
+        #[serde(default)]
+        otherwise: Vec<WorkflowStep>,
+    },
+    /// Run each branch concurrently and join once all have finished.
+    /// Variables bound inside branches are visible after the join; only the
+    /// keys a branch actually set are merged back. Two branches setting the
+    /// same variable is an error, except `last_reply` and `last_error`, which
+    /// take the value from the last branch (in order) that set them.
+    Parallel {
+        branches: Vec<Vec<WorkflowStep>>,
+    },
+    CloseOffice(OfficeRef),
+}
+
+/// Target of an `OpenOffice` step. Accepts the shorthand `OpenOffice: LegalOffice`
+/// or the long form `{office, alias, if_open}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenOfficeSpec {
+    pub office: OfficeType,
+    /// Step-local name later steps can use to refer to this window
+    pub alias: Option<String>,
+    pub if_open: DuplicatePolicy,
+}
+
+impl<'de> Deserialize<'de> for OpenOfficeSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Detailed {
+            office: OfficeType,
+            #[serde(default)]
+            alias: Option<String>,
+            #[serde(default)]
+            if_open: DuplicatePolicy,
+        }
+
+        // Dispatch on shape by hand so a bad office name reports serde's
+        // "unknown variant" error instead of an opaque untagged-enum failure
+        let value = Value::deserialize(deserializer)?;
+        if value.is_string() {
+            let office = OfficeType::deserialize(value).map_err(de::Error::custom)?;
+            return Ok(Self {
+                office,
+                alias: None,
+                if_open: DuplicatePolicy::default(),
+            });
+        }
+
+        let detailed = Detailed::deserialize(value).map_err(de::Error::custom)?;
+        Ok(Self {
+            office: detailed.office,
+            alias: detailed.alias,
+            if_open: detailed.if_open,
+        })
+    }
+}
+
+/// What `OpenOffice` does when a window of that office is already open
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicatePolicy {
+    /// Focus and use the existing window
+    #[default]
+    Reuse,
+    /// Open another window anyway
+    New,
+    /// Fail the step
+    Fail,
+}
+
+/// How a step refers to an open office
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum OfficeRef {
+    /// Every open window of this office type
+    Office {
+        office: OfficeType,
+    },
+    /// The window bound by an `OpenOffice` step's alias
+    Alias {
+        alias: String,
+    },
+    Window {
+        window_id: String,
+    },
+    /// Bare string: an alias, an office type name, or a window id (in that order)
+    Name(String),
+}
+
+impl<'de> Deserialize<'de> for OfficeRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let field = |key: &str| value.get(key).cloned();
+
+        if let Value::String(name) = &value {
+            return Ok(Self::Name(name.clone()));
+        }
+        if let Some(office) = field("office") {
+            let office = OfficeType::deserialize(office).map_err(de::Error::custom)?;
+            return Ok(Self::Office { office });
+        }
+        if let Some(Value::String(alias)) = field("alias") {
+            return Ok(Self::Alias { alias });
+        }
+        if let Some(Value::String(window_id)) = field("window_id") {
+            return Ok(Self::Window { window_id });
+        }
+        Err(de::Error::custom(
+            "expected an office name, alias or window id, or one of `office`, `alias`, `window_id`",
+        ))
+    }
+}
+
+/// `WaitForResponse` fields, from either `30` or `{timeout_secs: 30, ...}`
+fn deserialize_wait<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(u64, TimeoutPolicy), D::Error> {
+    #[derive(Deserialize)]
+    struct Detailed {
+        timeout_secs: u64,
+        #[serde(default)]
+        on_timeout: TimeoutPolicy,
+    }
+
+    let value = Value::deserialize(deserializer)?;
+    if value.is_number() {
+        let timeout_secs = u64::deserialize(value).map_err(de::Error::custom)?;
+        return Ok((timeout_secs, TimeoutPolicy::default()));
+    }
+
+    let detailed = Detailed::deserialize(value).map_err(de::Error::custom)?;
+    Ok((detailed.timeout_secs, detailed.on_timeout))
+}
+
+impl WorkflowAction {
+    /// Variant name, as reported in run status
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::OpenOffice(_) => "OpenOffice",
+            Self::SendMessage(..) => "SendMessage",
+            Self::WaitForResponse { .. } => "WaitForResponse",
+            Self::Ask { .. } => "Ask",
+            Self::SetVariable { .. } => "SetVariable",
+            Self::Branch { .. } => "Branch",
+            Self::Parallel { .. } => "Parallel",
+            Self::CloseOffice(_) => "CloseOffice",
+        }
+    }
+}
+
+/// What a `WaitForResponse` step does when no reply arrives in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutPolicy {
+    #[default]
+    Fail,
+    Skip,
+    Retry {
+        max_retries: u32,
+    },
+}
+
+/// Per-step error handling
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnError {
+    /// Abort the workflow
+    #[default]
+    Fail,
+    /// Record the error in `last_error` and carry on
+    Continue,
+    /// Record the error in `last_error` and run these steps instead
+    Run(Vec<WorkflowStep>),
+}
+
+/// Conditions over workflow variables; `var` accepts dotted paths
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Exists {
+        var: String,
+    },
+    Equals {
+        var: String,
+        value: Value,
+    },
+    /// Substring match for strings, element match for arrays
+    Contains {
+        var: String,
+        value: Value,
+    },
+    Not(Box<Condition>),
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+}
+
+/// Mutable state threaded through a workflow run
+struct WorkflowContext {
+    run_id: String,
+    runs: WorkflowRuns,
+    control: watch::Receiver<RunControl>,
+    variables: HashMap<String, Value>,
+    /// Variables set in this context since it was created or forked
+    written: HashSet<String>,
+    /// Window ids bound by `OpenOffice` aliases
+    aliases: HashMap<String, String>,
+    /// The request whose reply the next `WaitForResponse` step waits for
+    last_request: Option<(OfficeMessage, PendingReply)>,
+}
+
+impl WorkflowContext {
+    /// A fresh context for a parallel branch, seeded with the current variables
+    fn fork(&self) -> Self {
+        Self {
+            run_id: self.run_id.clone(),
+            runs: self.runs.clone(),
+            control: self.control.clone(),
+            variables: self.variables.clone(),
+            written: HashSet::new(),
+            aliases: self.aliases.clone(),
+            last_request: None,
+        }
+    }
+
+    fn set_variable(&mut self, name: &str, value: Value) {
+        self.variables.insert(name.to_string(), value);
+        self.written.insert(name.to_string());
+    }
+}
+
+/// Merge the variables each parallel branch set back into the parent scope.
+/// Keys a branch merely inherited are ignored, so an untouched copy never
+/// overwrites another branch's write.
+fn merge_branch_variables(
+    variables: &mut HashMap<String, Value>,
+    written: &mut HashSet<String>,
+    branches: Vec<(HashMap<String, Value>, HashSet<String>)>,
+) -> Result<(), String> {
+    let mut set_by: HashMap<String, usize> = HashMap::new();
+    for (index, (mut branch_variables, branch_written)) in branches.into_iter().enumerate() {
+        for name in branch_written {
+            let shared = name == LAST_REPLY_VARIABLE || name == LAST_ERROR_VARIABLE;
+            if let Some(other) = set_by.insert(name.clone(), index) {
+                if !shared {
+                    return Err(format!(
+                        "Parallel branches {} and {} both set variable '{}'",
+                        other, index, name
+                    ));
+                }
+            }
+            if let Some(value) = branch_variables.remove(&name) {
+                variables.insert(name.clone(), value);
+            }
+            written.insert(name);
+        }
+    }
+    Ok(())
+}
+
+fn step_path(prefix: &str, index: usize) -> String {
+    if prefix.is_empty() {
+        index.to_string()
+    } else {
+        format!("{}.{}", prefix, index)
+    }
+}
+
+/// Resolve `name.field.0` against the variables
+fn lookup<'a>(variables: &'a HashMap<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut parts = path.split('.');
+    let mut current = variables.get(parts.next()?.trim())?;
+    for part in parts {
+        current = match current {
+            Value::Object(map) => map.get(part)?,
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Render `{{path}}` placeholders. A string that is exactly one placeholder
+/// is replaced by the variable's JSON value; otherwise values are interpolated.
+fn render_template(value: &Value, variables: &HashMap<String, Value>) -> Result<Value, String> {
+    match value {
+        Value::String(s) => render_string(s, variables),
+        Value::Array(items) => items
+            .iter()
+            .map(|v| render_template(v, variables))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Value::Object(map) => map
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), render_template(v, variables)?)))
+            .collect::<Result<serde_json::Map<_, _>, String>>()
+            .map(Value::Object),
+        other => Ok(other.clone()),
+    }
+}
+
+fn render_string(s: &str, variables: &HashMap<String, Value>) -> Result<Value, String> {
+    let resolve = |path: &str| {
+        lookup(variables, path.trim()).ok_or_else(|| format!("Unknown variable: {}", path.trim()))
+    };
+
+    if let Some(path) = s
+        .strip_prefix("{{")
+        .and_then(|rest| rest.strip_suffix("}}"))
+    {
+        if !path.contains("{{") && !path.contains("}}") {
+            return resolve(path).cloned();
+        }
+    }
+
+    let mut rendered = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match resolve(&rest[start + 2..start + end])? {
+            Value::String(text) => rendered.push_str(text),
+            other => rendered.push_str(&other.to_string()),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(Value::String(rendered))
+}
+
+fn evaluate_condition(condition: &Condition, variables: &HashMap<String, Value>) -> bool {
+    match condition {
+        Condition::Exists { var } => lookup(variables, var).is_some_and(|v| !v.is_null()),
+        Condition::Equals { var, value } => lookup(variables, var) == Some(value),
+        Condition::Contains { var, value } => match (lookup(variables, var), value) {
+            (Some(Value::String(haystack)), Value::String(needle)) => haystack.contains(needle),
+            (Some(Value::Array(items)), needle) => items.contains(needle),
+            _ => false,
+        },
+        Condition::Not(inner) => !evaluate_condition(inner, variables),
+        Condition::All(conditions) => conditions.iter().all(|c| evaluate_condition(c, variables)),
+        Condition::Any(conditions) => conditions.iter().any(|c| evaluate_condition(c, variables)),
+    }
+}
+
+/// Start a workflow as a background run and return its run id immediately
+pub async fn start_workflow(
+    manager: Arc<RwLock<WindowManager>>,
+    workflow: WorkflowDefinition,
+) -> Result<String, String> {
+    let runs = manager.read().await.workflow_runs().clone();
+    let (run_id, control) = runs.register(&workflow).await;
+
+    let task_run_id = run_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut ctx = WorkflowContext {
+            run_id: task_run_id,
+            runs: runs.clone(),
+            control,
+            variables: HashMap::new(),
+            written: HashSet::new(),
+            aliases: HashMap::new(),
+            last_request: None,
+        };
+
+        let result = {
+            let manager = manager.read().await;
+            manager.run_steps(&workflow.steps, "", &mut ctx).await
+        };
+        runs.finish(&ctx.run_id, &result).await;
+    });
+
+    Ok(run_id)
+}
+
+impl WindowManager {
+    fn run_steps<'a>(
+        &'a self,
+        steps: &'a [WorkflowStep],
+        prefix: &'a str,
+        ctx: &'a mut WorkflowContext,
+    ) -> BoxFuture<'a, Result<(), String>> {
+        Box::pin(async move {
+            for (index, step) in steps.iter().enumerate() {
+                let path = step_path(prefix, index);
+                workflow_runs::checkpoint(&mut ctx.control).await?;
+
+                let runs = ctx.runs.clone();
+                let run_id = ctx.run_id.clone();
+                runs.step_started(&run_id, &path, step.label(), step.action.name())
+                    .await;
+
+                // Cancellation interrupts the step even while it is waiting on a reply
+                let cancel = workflow_runs::cancelled(ctx.control.clone());
+                let result = tokio::select! {
+                    result = self.run_step(step, &path, ctx) => result,
+                    _ = cancel => Err(CANCELLED.to_string()),
+                };
+
+                runs.step_finished(&run_id, &path, result.as_ref().err().map(String::as_str))
+                    .await;
+
+                let error = match result {
+                    Ok(output) => {
+                        if let (Some(name), Some(output)) = (&step.bind, output) {
+                            ctx.set_variable(name, output);
+                        }
+                        continue;
+                    }
+                    Err(e) if e == CANCELLED => return Err(e),
+                    Err(e) => e,
+                };
+
+                match &step.on_error {
+                    OnError::Fail => {
+                        return Err(format!("Step '{}' failed: {}", step.label(), error));
+                    }
+                    OnError::Continue => {
+                        ctx.set_variable(LAST_ERROR_VARIABLE, Value::String(error));
+                    }
+                    OnError::Run(handler) => {
+                        ctx.set_variable(LAST_ERROR_VARIABLE, Value::String(error));
+                        let handler_prefix = format!("{}.on_error", path);
+                        self.run_steps(handler, &handler_prefix, ctx).await?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Execute one step, returning its output value (if any)
+    async fn run_step(
+        &self,
+        step: &WorkflowStep,
+        path: &str,
+        ctx: &mut WorkflowContext,
+    ) -> Result<Option<Value>, String> {
+        match &step.action {
+            WorkflowAction::OpenOffice(spec) => {
+                let window_id = self.open_step_office(&spec.office, spec.if_open).await?;
+                if let Some(alias) = &spec.alias {
+                    ctx.aliases.insert(alias.clone(), window_id.clone());
+                }
+                Ok(Some(Value::String(window_id)))
+            }
+            WorkflowAction::SendMessage(office_type, message) => {
+                let correlation_id = self.send_step_request(office_type, message, ctx).await?;
+                Ok(Some(Value::String(correlation_id)))
+            }
+            WorkflowAction::WaitForResponse {
+                timeout_secs,
+                on_timeout,
+            } => self.wait_step_reply(*timeout_secs, on_timeout, ctx).await,
+            WorkflowAction::Ask {
+                office,
+                message,
+                timeout_secs,
+                on_timeout,
+            } => {
+                self.send_step_request(office, message, ctx).await?;
+                self.wait_step_reply(*timeout_secs, on_timeout, ctx).await
+            }
+            WorkflowAction::SetVariable { name, value } => {
+                let rendered = render_template(value, &ctx.variables)?;
+                ctx.set_variable(name, rendered.clone());
+                Ok(Some(rendered))
+            }
+            WorkflowAction::Branch {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let (steps, arm) = if evaluate_condition(condition, &ctx.variables) {
+                    (then, "then")
+                } else {
+                    (otherwise, "otherwise")
+                };
+                let prefix = format!("{}.{}", path, arm);
+                self.run_steps(steps, &prefix, ctx).await?;
+                Ok(None)
+            }
+            WorkflowAction::Parallel { branches } => {
+                let mut forks: Vec<WorkflowContext> = branches.iter().map(|_| ctx.fork()).collect();
+                let prefixes: Vec<String> = (0..branches.len())
+                    .map(|i| format!("{}.branch{}", path, i))
+                    .collect();
+                let results = join_all(
+                    branches
+                        .iter()
+                        .zip(prefixes.iter())
+                        .zip(forks.iter_mut())
+                        .map(|((steps, prefix), fork)| self.run_steps(steps, prefix, fork)),
+                )
+                .await;
+
+                if let Some(error) = results.into_iter().find_map(Result::err) {
+                    return Err(error);
+                }
+
+                // Join: merge back only what each branch set, in branch order
+                let mut replies = Vec::with_capacity(forks.len());
+                let mut branch_variables = Vec::with_capacity(forks.len());
+                for fork in forks {
+                    replies.push(
+                        fork.variables
+                            .get(LAST_REPLY_VARIABLE)
+                            .filter(|_| fork.written.contains(LAST_REPLY_VARIABLE))
+                            .cloned()
+                            .unwrap_or(Value::Null),
+                    );
+                    for (alias, window_id) in fork.aliases {
+                        if ctx.aliases.get(&alias) != Some(&window_id) {
+                            ctx.aliases.insert(alias, window_id);
+                        }
+                    }
+                    branch_variables.push((fork.variables, fork.written));
+                }
+                merge_branch_variables(&mut ctx.variables, &mut ctx.written, branch_variables)?;
+                Ok(Some(Value::Array(replies)))
+            }
+            WorkflowAction::CloseOffice(office_ref) => {
+                for window_id in self.resolve_office_ref(office_ref, ctx).await? {
+                    self.close_office_window(&window_id).await?;
+                    ctx.aliases.retain(|_, bound| *bound != window_id);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Open an office for a workflow step, honoring the duplicate policy
+    async fn open_step_office(
+        &self,
+        office_type: &OfficeType,
+        if_open: DuplicatePolicy,
+    ) -> Result<String, String> {
+        // Check and open under one lock, so parallel branches targeting the
+        // same office see each other's windows
+        let _guard = self.lock_opening().await;
+        let existing = self.find_office_windows(office_type).await;
+
+        match (existing.first(), if_open) {
+            (Some(window_id), DuplicatePolicy::Reuse) => {
+                if let Some(window) = self.app_handle().get_window(window_id) {
+                    window.set_focus().ok();
+                }
+                Ok(window_id.clone())
+            }
+            (Some(_), DuplicatePolicy::Fail) => Err(format!("{} is already open", office_type)),
+            (existing, if_open) => {
+                let opened = self
+                    .open_office_locked(office_type.clone(), true, None, None)
+                    .await?;
+                if existing.is_some() && if_open == DuplicatePolicy::New && !opened.created {
+                    return Err(format!(
+                        "{} does not allow another window ({:?})",
+                        office_type,
+                        self.instance_policy(office_type).await
+                    ));
+                }
+                Ok(opened.window_id)
+            }
+        }
+    }
+
+    /// Resolve an office reference to the window ids it currently names
+    async fn resolve_office_ref(
+        &self,
+        office_ref: &OfficeRef,
+        ctx: &WorkflowContext,
+    ) -> Result<Vec<String>, String> {
+        let by_alias = |alias: &str| {
+            ctx.aliases
+                .get(alias)
+                .cloned()
+                .ok_or_else(|| format!("Unknown office alias: {}", alias))
+        };
+
+        match office_ref {
+            OfficeRef::Office { office } => Ok(self.find_office_windows(office).await),
+            OfficeRef::Alias { alias } => by_alias(alias).map(|id| vec![id]),
+            OfficeRef::Window { window_id } => Ok(vec![window_id.clone()]),
+            OfficeRef::Name(name) => {
+                if let Ok(window_id) = by_alias(name) {
+                    return Ok(vec![window_id]);
+                }
+                match serde_json::from_value::<OfficeType>(Value::String(name.clone())) {
+                    Ok(office) => Ok(self.find_office_windows(&office).await),
+                    Err(_) => Ok(vec![name.clone()]),
+                }
+            }
+        }
+    }
+
+    async fn send_step_request(
+        &self,
+        office_type: &OfficeType,
+        message: &Value,
+        ctx: &mut WorkflowContext,
+    ) -> Result<String, String> {
+        let mut request = OfficeMessage::new(
+            MessageTarget::Office(office_type.clone()),
+            WORKFLOW_TOPIC,
+            render_template(message, &ctx.variables)?,
+        );
+        request.from = Some(format!("workflow:{}", ctx.run_id));
+
+        let pending = self.message_bus().send_request(request.clone()).await?;
+        let correlation_id = pending.correlation_id.clone();
+        ctx.last_request = Some((request, pending));
+        Ok(correlation_id)
+    }
+
+    async fn wait_step_reply(
+        &self,
+        timeout_secs: u64,
+        policy: &TimeoutPolicy,
+        ctx: &mut WorkflowContext,
+    ) -> Result<Option<Value>, String> {
+        let (request, pending) = ctx
+            .last_request
+            .take()
+            .ok_or_else(|| "No message was sent to wait for".to_string())?;
+
+        let reply = self
+            .await_reply(request, pending, timeout_secs, policy)
+            .await?
+            .map(|reply| reply.payload);
+
+        if let Some(payload) = &reply {
+            ctx.set_variable(LAST_REPLY_VARIABLE, payload.clone());
+        }
+        Ok(reply)
+    }
+
+    /// Wait for a workflow request's reply, applying the timeout policy.
+    /// Returns `None` when the step was skipped after a timeout.
+    async fn await_reply(
+        &self,
+        mut request: OfficeMessage,
+        mut pending: PendingReply,
+        timeout_secs: u64,
+        policy: &TimeoutPolicy,
+    ) -> Result<Option<OfficeMessage>, String> {
+        let timeout = Duration::from_secs(timeout_secs);
+        let mut retries = 0;
+
+        loop {
+            let error = match pending.wait(timeout).await {
+                Ok(reply) => return Ok(Some(reply)),
+                Err(e) => e,
+            };
+
+            match policy {
+                TimeoutPolicy::Fail => return Err(error),
+                TimeoutPolicy::Skip => return Ok(None),
+                TimeoutPolicy::Retry { max_retries } if retries < *max_retries => {
+                    retries += 1;
+                    request.id = String::new();
+                    request.correlation_id = None;
+                    pending = self.message_bus().send_request(request.clone()).await?;
+                }
+                TimeoutPolicy::Retry { .. } => {
+                    return Err(format!("{} (after {} retries)", error, retries));
+                }
+            }
+        }
+    }
+}
+
+/// Tauri commands for multi-office workflows
+///
+/// Returns the run id as soon as the workflow has been started; progress is
+/// reported through `unity:office_workflow_step`, the outcome through
+/// `unity:workflow_run_finished`, and both through the run commands.
+#[tauri::command]
+pub async fn orchestrate_office_workflow(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    workflow: WorkflowDefinition,
+) -> Result<String, String> {
+    start_workflow(state.inner().clone(), workflow).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn branch(
+        base: &HashMap<String, Value>,
+        sets: &[(&str, Value)],
+    ) -> (HashMap<String, Value>, HashSet<String>) {
+        let mut variables = base.clone();
+        let mut written = HashSet::new();
+        for (name, value) in sets {
+            variables.insert(name.to_string(), value.clone());
+            written.insert(name.to_string());
+        }
+        (variables, written)
+    }
+
+    #[test]
+    fn untouched_branch_does_not_overwrite_a_sibling_write() {
+        let mut variables = HashMap::from([("status".to_string(), json!("draft"))]);
+        let mut written = HashSet::new();
+        let branches = vec![
+            branch(&variables, &[("status", json!("reviewed"))]),
+            branch(&variables, &[]),
+        ];
+
+        merge_branch_variables(&mut variables, &mut written, branches).unwrap();
+
+        assert_eq!(variables["status"], json!("reviewed"));
+        assert!(written.contains("status"));
+    }
+
+    #[test]
+    fn branches_setting_the_same_variable_conflict() {
+        let mut variables = HashMap::new();
+        let branches = vec![
+            branch(&variables, &[("verdict", json!("approve"))]),
+            branch(&variables, &[("verdict", json!("reject"))]),
+        ];
+
+        let error =
+            merge_branch_variables(&mut variables, &mut HashSet::new(), branches).unwrap_err();
+        assert!(error.contains("'verdict'"), "{}", error);
+    }
+
+    #[test]
+    fn last_reply_takes_the_last_branch_that_set_it() {
+        let mut variables = HashMap::new();
+        let branches = vec![
+            branch(&variables, &[(LAST_REPLY_VARIABLE, json!(1))]),
+            branch(&variables, &[(LAST_REPLY_VARIABLE, json!(2))]),
+            branch(&variables, &[]),
+        ];
+
+        merge_branch_variables(&mut variables, &mut HashSet::new(), branches).unwrap();
+        assert_eq!(variables[LAST_REPLY_VARIABLE], json!(2));
+    }
+
+    #[test]
+    fn wait_for_response_still_accepts_a_bare_timeout() {
+        for (raw, retries) in [
+            (json!({"WaitForResponse": 30}), None),
+            (json!({"WaitForResponse": {"timeout_secs": 30}}), None),
+            (
+                json!({"WaitForResponse": {"timeout_secs": 30, "on_timeout": {"retry": {"max_retries": 2}}}}),
+                Some(2),
+            ),
+        ] {
+            let action: WorkflowAction = serde_json::from_value(raw).unwrap();
+            let WorkflowAction::WaitForResponse {
+                timeout_secs,
+                on_timeout,
+            } = action
+            else {
+                panic!("not a WaitForResponse");
+            };
+            assert_eq!(timeout_secs, 30);
+            let max_retries = match on_timeout {
+                TimeoutPolicy::Retry { max_retries } => Some(max_retries),
+                _ => None,
+            };
+            assert_eq!(max_retries, retries);
+        }
+        assert!(
+            serde_json::from_value::<WorkflowAction>(json!({"WaitForResponse": "soon"})).is_err()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Window;
+use tokio::sync::{oneshot, Mutex};
+use uuid::Uuid;
+
+use crate::model_scheduler::ModelScheduler;
+use crate::util::mean;
+
+/// Local Ollama server started as a sidecar
+pub const OLLAMA_BASE_URL: &str = "http://127.0.0.1:11434";
+
+/// Number of recent streams averaged into the `tokens_per_sec` metric
+const THROUGHPUT_WINDOW: usize = 20;
+
+/// Streams older than this no longer count towards `tokens_per_sec`
+const THROUGHPUT_MAX_AGE_SECS: u64 = 300;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Body of an `ollama_chat` call (mirrors Ollama's `/api/chat`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<Value>,
+}
+
+/// Body of an `ollama_generate` call (mirrors Ollama's `/api/generate`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerateRequest {
+    pub model: String,
+    pub prompt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_alive: Option<Value>,
+}
+
+/// Payload of `ollama:token`, one per streamed chunk
+#[derive(Debug, Clone, Serialize)]
+struct TokenEvent<'a> {
+    stream_id: &'a str,
+    content: &'a str,
+}
+
+/// Payload of `ollama:done`, sent once a stream ends (completed or cancelled)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamSummary {
+    pub stream_id: String,
+    pub model: String,
+    pub content: String,
+    pub tokens: u64,
+    pub duration_ms: f64,
+    pub tokens_per_sec: f64,
+    pub cancelled: bool,
+}
+
+/// Payload of `ollama:error`
+#[derive(Debug, Clone, Serialize)]
+struct ErrorEvent<'a> {
+    stream_id: &'a str,
+    error: &'a str,
+}
+
+#[derive(Clone, Copy)]
+enum Endpoint {
+    Chat,
+    Generate,
+}
+
+impl Endpoint {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Chat => "/api/chat",
+            Self::Generate => "/api/generate",
+        }
+    }
+
+    /// Text carried by one NDJSON chunk of this endpoint's stream
+    fn chunk_text(self, chunk: &Value) -> &str {
+        let text = match self {
+            Self::Chat => &chunk["message"]["content"],
+            Self::Generate => &chunk["response"],
+        };
+        text.as_str().unwrap_or("")
+    }
+}
+
+/// Newline-delimited JSON objects read incrementally from a streaming response
+pub struct NdjsonStream {
+    response: reqwest::Response,
+    buffer: Vec<u8>,
+}
+
+impl NdjsonStream {
+    /// Wrap a response, turning a non-success status into an error
+    pub async fn new(response: reqwest::Response) -> Result<Self, String> {
+        if !response.status().is_success() {
+            let status = response.status();
+            let detail = response.text().await.unwrap_or_default();
+            return Err(format!("Ollama returned {}: {}", status, detail.trim()));
+        }
+
+        Ok(Self {
+            response,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// The next object, or `None` once the body ends. Objects carrying an
+    /// `error` field are returned as errors.
+    pub async fn next(&mut self) -> Result<Option<Value>, String> {
+        loop {
+            if let Some(newline) = self.buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+                let line = String::from_utf8_lossy(&line);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                return Self::parse(line.trim()).map(Some);
+            }
+
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .map_err(|e| format!("Ollama stream failed: {}", e))?;
+            match chunk {
+                Some(bytes) => self.buffer.extend_from_slice(&bytes),
+                None => {
+                    // A final object without a trailing newline
+                    let rest = String::from_utf8_lossy(&self.buffer).trim().to_string();
+                    self.buffer.clear();
+                    if rest.is_empty() {
+                        return Ok(None);
+                    }
+                    return Self::parse(&rest).map(Some);
+                }
+            }
+        }
+    }
+
+    fn parse(line: &str) -> Result<Value, String> {
+        let value: Value = serde_json::from_str(line)
+            .map_err(|e| format!("Malformed Ollama stream chunk: {}", e))?;
+        match value["error"].as_str() {
+            Some(error) => Err(format!("Ollama error: {}", error)),
+            None => Ok(value),
+        }
+    }
+}
+
+/// Native client for the local Ollama server, streaming tokens to office windows
+#[derive(Clone)]
+pub struct OllamaClient {
+    base_url: String,
+    http: reqwest::Client,
+    streams: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+    throughput: Arc<Mutex<VecDeque<(Instant, f64)>>>,
+}
+
+impl OllamaClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            streams: Arc::new(Mutex::new(HashMap::new())),
+            throughput: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    /// Absolute URL of an Ollama API path
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// Start streaming a chat completion to `window`, returning the stream id
+    pub async fn chat(&self, window: Window, request: ChatRequest) -> Result<String, String> {
+        if request.messages.is_empty() {
+            return Err("Chat request needs at least one message".to_string());
+        }
+        let model = request.model.clone();
+        let body = serde_json::to_value(&request).map_err(|e| e.to_string())?;
+        self.start_stream(window, Endpoint::Chat, model, body).await
+    }
+
+    /// Start streaming a completion for a raw prompt to `window`, returning the stream id
+    pub async fn generate(
+        &self,
+        window: Window,
+        request: GenerateRequest,
+    ) -> Result<String, String> {
+        let model = request.model.clone();
+        let body = serde_json::to_value(&request).map_err(|e| e.to_string())?;
+        self.start_stream(window, Endpoint::Generate, model, body)
+            .await
+    }
+
+    async fn start_stream(
+        &self,
+        window: Window,
+        endpoint: Endpoint,
+        model: String,
+        mut body: Value,
+    ) -> Result<String, String> {
+        if model.trim().is_empty() {
+            return Err("Model name cannot be empty".to_string());
+        }
+        body["stream"] = Value::Bool(true);
+
+        let stream_id = Uuid::new_v4().to_string();
+        let (cancel_tx, cancel_rx) = oneshot::channel();
+        self.streams
+            .lock()
+            .await
+            .insert(stream_id.clone(), cancel_tx);
+
+        let client = self.clone();
+        let id = stream_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let result = client
+                .stream(&window, endpoint, &id, &model, &body, cancel_rx)
+                .await;
+            client.streams.lock().await.remove(&id);
+
+            match result {
+                Ok(summary) => {
+                    if !summary.cancelled && summary.tokens > 0 {
+                        client.record_throughput(summary.tokens_per_sec).await;
+                    }
+                    window.emit("ollama:done", summary).ok();
+                }
+                Err(error) => {
+                    let event = ErrorEvent {
+                        stream_id: &id,
+                        error: &error,
+                    };
+                    window.emit("ollama:error", event).ok();
+                }
+            }
+        });
+
+        Ok(stream_id)
+    }
+
+    async fn stream(
+        &self,
+        window: &Window,
+        endpoint: Endpoint,
+        stream_id: &str,
+        model: &str,
+        body: &Value,
+        mut cancel: oneshot::Receiver<()>,
+    ) -> Result<StreamSummary, String> {
+        let started = Instant::now();
+        let response = self
+            .http
+            .post(self.url(endpoint.path()))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| format!("Ollama request failed: {}", e))?;
+        let mut lines = NdjsonStream::new(response).await?;
+
+        let mut summary = StreamSummary {
+            stream_id: stream_id.to_string(),
+            model: model.to_string(),
+            content: String::new(),
+            tokens: 0,
+            duration_ms: 0.0,
+            tokens_per_sec: 0.0,
+            cancelled: false,
+        };
+        // Ollama's own eval counters, reported on the final chunk
+        let mut eval: Option<(u64, u64)> = None;
+
+        loop {
+            let value = tokio::select! {
+                _ = &mut cancel => {
+                    summary.cancelled = true;
+                    break;
+                }
+                value = lines.next() => value?,
+            };
+            let Some(value) = value else {
+                break;
+            };
+
+            let text = endpoint.chunk_text(&value);
+            if !text.is_empty() {
+                summary.tokens += 1;
+                summary.content.push_str(text);
+                let event = TokenEvent {
+                    stream_id,
+                    content: text,
+                };
+                window.emit("ollama:token", event).ok();
+            }
+
+            if value["done"].as_bool() == Some(true) {
+                if let (Some(count), Some(duration)) = (
+                    value["eval_count"].as_u64(),
+                    value["eval_duration"].as_u64(),
+                ) {
+                    eval = Some((count, duration));
+                }
+                break;
+            }
+        }
+
+        let elapsed = started.elapsed().as_secs_f64();
+        summary.duration_ms = elapsed * 1000.0;
+        summary.tokens_per_sec = match eval {
+            Some((count, duration_ns)) if duration_ns > 0 => {
+                summary.tokens = count;
+                count as f64 / (duration_ns as f64 / 1e9)
+            }
+            _ if elapsed > 0.0 => summary.tokens as f64 / elapsed,
+            _ => 0.0,
+        };
+
+        Ok(summary)
+    }
+
+    /// Stop a running stream; the window still receives `ollama:done` with `cancelled: true`
+    pub async fn cancel(&self, stream_id: &str) -> Result<(), String> {
+        let cancel = self
+            .streams
+            .lock()
+            .await
+            .remove(stream_id)
+            .ok_or_else(|| format!("No active Ollama stream: {}", stream_id))?;
+        cancel.send(()).ok();
+        Ok(())
+    }
+
+    async fn record_throughput(&self, tokens_per_sec: f64) {
+        let mut throughput = self.throughput.lock().await;
+        if throughput.len() >= THROUGHPUT_WINDOW {
+            throughput.pop_front();
+        }
+        throughput.push_back((Instant::now(), tokens_per_sec));
+    }
+
+    /// Average tokens/sec of recent streams, if any finished lately
+    pub async fn recent_tokens_per_sec(&self) -> Option<f64> {
+        let max_age = Duration::from_secs(THROUGHPUT_MAX_AGE_SECS);
+        let throughput = self.throughput.lock().await;
+        let recent: Vec<f64> = throughput
+            .iter()
+            .filter(|(at, _)| at.elapsed() <= max_age)
+            .map(|(_, tps)| *tps)
+            .collect();
+        mean(&recent)
+    }
+}
+
+/// Tauri commands for streaming Ollama completions
+#[tauri::command]
+pub async fn ollama_chat(
+    window: Window,
+    client: tauri::State<'_, OllamaClient>,
+    scheduler: tauri::State<'_, ModelScheduler>,
+    request: ChatRequest,
+) -> Result<String, String> {
+    scheduler.admit(&client, &window, &request.model).await?;
+    client.chat(window, request).await
+}
+
+#[tauri::command]
+pub async fn ollama_generate(
+    window: Window,
+    client: tauri::State<'_, OllamaClient>,
+    scheduler: tauri::State<'_, ModelScheduler>,
+    request: GenerateRequest,
+) -> Result<String, String> {
+    scheduler.admit(&client, &window, &request.model).await?;
+    client.generate(window, request).await
+}
+
+#[tauri::command]
+pub async fn cancel_ollama_stream(
+    client: tauri::State<'_, OllamaClient>,
+    stream_id: String,
+) -> Result<(), String> {
+    client.cancel(&stream_id).await
+}
@@ -0,0 +1,799 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::configs::{config_path, EvalConfig};
+use crate::evolution_history::{EvaluationRecord, EvolutionHistory};
+use crate::schemas::{FieldError, IpcError, SchemaViolation};
+use crate::util::{now_secs, write_atomic};
+
+/// File (inside the app data dir) holding locally edited rubrics
+const RUBRICS_FILE: &str = "rubrics.json";
+
+/// Version of the rubric eval.yaml held before anything was pushed; it is
+/// frozen into the store by the first push
+pub const BASE_VERSION: &str = "v1";
+
+/// Allowed slack when checking that weights sum to 1.0
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+/// Evaluations replayed by a routing preview when no limit is given
+const DEFAULT_PREVIEW_LIMIT: usize = 500;
+
+/// `routing_path` values reported by the backend evaluator
+const HEURISTIC_REJECT: &str = "heuristic_reject";
+const HEURISTIC_ACCEPT: &str = "heuristic_accept";
+const LLM_JUDGE: &str = "llm_judge";
+
+/// Scoring weights plus the two-tier routing thresholds, under a version
+/// name that requests pass as `rubric_version`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rubric {
+    pub version: String,
+    pub weights: BTreeMap<String, f64>,
+    pub tau_low: f64,
+    pub tau_high: f64,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub created_at: f64,
+    /// When the rubric was written to eval.yaml; pushed rubrics are frozen
+    #[serde(default)]
+    pub pushed_at: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RubricStore {
+    active_version: Option<String>,
+    rubrics: Vec<Rubric>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RubricList {
+    /// Version whose rubric eval.yaml currently holds
+    pub active_version: String,
+    pub rubrics: Vec<Rubric>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoutingChange {
+    pub evaluation_id: i64,
+    pub goal: String,
+    pub rubric_version: String,
+    pub quality_score: f64,
+    pub old_path: String,
+    /// `None` when the outcome depends on a heuristic score that was not recorded
+    pub new_path: Option<String>,
+    pub possible_paths: Vec<String>,
+}
+
+/// How past evaluations would have been routed under a candidate rubric
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingPreview {
+    pub version: String,
+    pub evaluations: usize,
+    pub unchanged: usize,
+    pub changed: usize,
+    /// LLM-judged evaluations whose heuristic score is unknown and could move
+    pub uncertain: usize,
+    /// Evaluations with a routing path the preview does not know
+    pub skipped: usize,
+    /// Counts keyed `old_path -> new_path`
+    pub transitions: BTreeMap<String, usize>,
+    pub llm_judge_before: usize,
+    pub llm_judge_after_min: usize,
+    pub llm_judge_after_max: usize,
+    pub changes: Vec<RoutingChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PushResult {
+    pub version: String,
+    pub path: String,
+    /// The backend reads eval.yaml at startup
+    pub restart_required: bool,
+}
+
+impl Rubric {
+    fn from_config(config: &EvalConfig) -> Self {
+        Self {
+            version: BASE_VERSION.to_string(),
+            weights: config.rubric_weights.clone(),
+            tau_low: config.tau_low,
+            tau_high: config.tau_high,
+            description: "From eval.yaml".to_string(),
+            created_at: 0.0,
+            pushed_at: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), SchemaViolation> {
+        let mut errors = Vec::new();
+
+        let valid_version = !self.version.is_empty()
+            && self
+                .version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_.-".contains(c));
+        if !valid_version {
+            errors.push(FieldError::new(
+                "version",
+                "must be non-empty and use only letters, digits, '_', '.' or '-'",
+            ));
+        }
+
+        if self.weights.is_empty() {
+            errors.push(FieldError::new(
+                "weights",
+                "at least one weight is required",
+            ));
+        }
+        for (name, weight) in &self.weights {
+            if !(0.0..=1.0).contains(weight) {
+                errors.push(FieldError::new(
+                    &format!("weights.{}", name),
+                    format!("{} is outside 0.0..=1.0", weight),
+                ));
+            }
+        }
+        let sum: f64 = self.weights.values().sum();
+        if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            errors.push(FieldError::new(
+                "weights",
+                format!("weights sum to {:.6}, expected 1.0", sum),
+            ));
+        }
+
+        for (field, tau) in [("tau_low", self.tau_low), ("tau_high", self.tau_high)] {
+            if !(0.0..=1.0).contains(&tau) {
+                errors.push(FieldError::new(
+                    field,
+                    format!("{} is outside 0.0..=1.0", tau),
+                ));
+            }
+        }
+        if self.tau_low >= self.tau_high {
+            errors.push(FieldError::new("tau_low", "must be below tau_high"));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SchemaViolation::new(EvalConfig::FILE, errors))
+        }
+    }
+
+    /// Route a heuristic score (0..1) the way the backend evaluator does
+    fn route(&self, score: f64) -> &'static str {
+        if score < self.tau_low {
+            HEURISTIC_REJECT
+        } else if score > self.tau_high {
+            HEURISTIC_ACCEPT
+        } else {
+            LLM_JUDGE
+        }
+    }
+
+    /// Paths an evaluation could take under `self`, given how `baseline` routed it
+    fn possible_routes(
+        &self,
+        baseline: &Rubric,
+        evaluation: &EvaluationRecord,
+    ) -> Vec<&'static str> {
+        match evaluation.routing_path.as_str() {
+            // Heuristic routes report the heuristic score itself
+            HEURISTIC_REJECT | HEURISTIC_ACCEPT => {
+                vec![self.route(evaluation.quality_score / 100.0)]
+            }
+            // The heuristic score lay somewhere in [tau_low, tau_high]
+            LLM_JUDGE => {
+                let (low, high) = (baseline.tau_low, baseline.tau_high);
+                let mut routes = Vec::new();
+                if low < self.tau_low {
+                    routes.push(HEURISTIC_REJECT);
+                }
+                if low.max(self.tau_low) <= high.min(self.tau_high) {
+                    routes.push(LLM_JUDGE);
+                }
+                if high > self.tau_high {
+                    routes.push(HEURISTIC_ACCEPT);
+                }
+                routes
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Replay past evaluations against `candidate`. Each evaluation is judged
+/// against the rubric of its own version, falling back to `base`.
+pub fn preview_routing(
+    candidate: &Rubric,
+    rubrics: &[Rubric],
+    base: &Rubric,
+    evaluations: &[EvaluationRecord],
+) -> RoutingPreview {
+    let mut preview = RoutingPreview {
+        version: candidate.version.clone(),
+        evaluations: evaluations.len(),
+        ..Default::default()
+    };
+
+    for evaluation in evaluations {
+        let baseline = rubrics
+            .iter()
+            .find(|r| r.version == evaluation.rubric_version)
+            .unwrap_or(base);
+        let routes = candidate.possible_routes(baseline, evaluation);
+        if routes.is_empty() {
+            preview.skipped += 1;
+            continue;
+        }
+
+        let old_path = evaluation.routing_path.as_str();
+        if old_path == LLM_JUDGE {
+            preview.llm_judge_before += 1;
+        }
+        if routes.contains(&LLM_JUDGE) {
+            preview.llm_judge_after_max += 1;
+            if routes.len() == 1 {
+                preview.llm_judge_after_min += 1;
+            }
+        }
+
+        let new_path = (routes.len() == 1).then(|| routes[0].to_string());
+        match &new_path {
+            Some(path) if path == old_path => {
+                preview.unchanged += 1;
+                continue;
+            }
+            Some(path) => {
+                preview.changed += 1;
+                *preview
+                    .transitions
+                    .entry(format!("{} -> {}", old_path, path))
+                    .or_default() += 1;
+            }
+            None => preview.uncertain += 1,
+        }
+
+        preview.changes.push(RoutingChange {
+            evaluation_id: evaluation.id,
+            goal: evaluation.goal.clone(),
+            rubric_version: evaluation.rubric_version.clone(),
+            quality_score: evaluation.quality_score,
+            old_path: old_path.to_string(),
+            new_path,
+            possible_paths: routes.iter().map(|r| r.to_string()).collect(),
+        });
+    }
+    preview
+}
+
+/// Rewrite a YAML scalar line `key: value  # comment`, keeping indentation,
+/// the comment and (where it fits) the comment's alignment
+fn replace_value(line: &str, value: &str) -> String {
+    let Some(colon) = line.find(':') else {
+        return line.to_string();
+    };
+    let (head, rest) = line.split_at(colon + 1);
+    match rest.find('#') {
+        Some(hash) => {
+            let width = hash.max(value.len() + 2);
+            format!("{}{:<width$}{}", head, format!(" {}", value), &rest[hash..])
+        }
+        None => format!("{} {}", head, value),
+    }
+}
+
+/// Write a rubric's thresholds and weights into eval.yaml text in place, so
+/// comments and unrelated sections survive
+fn apply_to_yaml(raw: &str, rubric: &Rubric) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut seen_tau_low = false;
+    let mut seen_tau_high = false;
+    let mut seen_weights = false;
+    let mut in_weights = false;
+    let mut written: BTreeSet<&str> = BTreeSet::new();
+    // Where keys missing from the file go: after the block's last entry
+    let mut weights_end = 0;
+
+    let missing_weights = |written: &BTreeSet<&str>| -> Vec<String> {
+        rubric
+            .weights
+            .iter()
+            .filter(|(name, _)| !written.contains(name.as_str()))
+            .map(|(name, weight)| format!("  {}: {}", name, weight))
+            .collect()
+    };
+
+    for line in raw.lines() {
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        let content = line.trim();
+        let is_entry = !content.is_empty() && !content.starts_with('#');
+
+        if in_weights && !indented && is_entry {
+            in_weights = false;
+        }
+
+        if in_weights && indented && is_entry {
+            let name = content.split(':').next().unwrap_or("").trim();
+            if let Some((key, weight)) = rubric.weights.get_key_value(name) {
+                out.push(replace_value(line, &weight.to_string()));
+                written.insert(key.as_str());
+                weights_end = out.len();
+            }
+            continue;
+        }
+
+        if line.starts_with("TAU_LOW:") {
+            seen_tau_low = true;
+            out.push(replace_value(line, &rubric.tau_low.to_string()));
+        } else if line.starts_with("TAU_HIGH:") {
+            seen_tau_high = true;
+            out.push(replace_value(line, &rubric.tau_high.to_string()));
+        } else if line.starts_with("RUBRIC_WEIGHTS:") {
+            seen_weights = true;
+            in_weights = true;
+            out.push(line.to_string());
+            weights_end = out.len();
+        } else {
+            out.push(line.to_string());
+        }
+    }
+
+    if seen_weights {
+        let missing = missing_weights(&written);
+        out.splice(weights_end..weights_end, missing);
+    }
+    if !seen_tau_low {
+        out.push(format!("TAU_LOW: {}", rubric.tau_low));
+    }
+    if !seen_tau_high {
+        out.push(format!("TAU_HIGH: {}", rubric.tau_high));
+    }
+    if !seen_weights {
+        out.push("RUBRIC_WEIGHTS:".to_string());
+        out.extend(missing_weights(&BTreeSet::new()));
+    }
+
+    let mut text = out.join("\n");
+    text.push('\n');
+    text
+}
+
+/// Locally edited rubric versions, and pushing one to eval.yaml
+#[derive(Clone)]
+pub struct RubricManager {
+    path: Option<PathBuf>,
+    store: Arc<Mutex<RubricStore>>,
+}
+
+impl RubricManager {
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let path = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(RUBRICS_FILE));
+        let store = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            store: Arc::new(Mutex::new(store)),
+        }
+    }
+
+    fn persist(&self, store: &RubricStore) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let raw = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+        std::fs::write(path, raw).map_err(|e| format!("Failed to save rubrics: {}", e))
+    }
+
+    /// Version `v1`: the frozen one, otherwise read from eval.yaml as long as
+    /// nothing has been pushed over it yet
+    fn base(store: &RubricStore) -> Result<Option<Rubric>, String> {
+        if let Some(saved) = store.rubrics.iter().find(|r| r.version == BASE_VERSION) {
+            return Ok(Some(saved.clone()));
+        }
+        if store.active_version.is_some() {
+            return Ok(None);
+        }
+        EvalConfig::load().map(|config| Some(Rubric::from_config(&config)))
+    }
+
+    /// Saved rubrics, plus `v1` from eval.yaml while it is not in the store
+    fn rubrics(store: &RubricStore) -> Result<Vec<Rubric>, String> {
+        let mut rubrics = store.rubrics.clone();
+        if !rubrics.iter().any(|r| r.version == BASE_VERSION) {
+            if let Some(base) = Self::base(store)? {
+                rubrics.insert(0, base);
+            }
+        }
+        Ok(rubrics)
+    }
+
+    fn find(store: &RubricStore, version: &str) -> Result<Rubric, String> {
+        Self::rubrics(store)?
+            .into_iter()
+            .find(|r| r.version == version)
+            .ok_or_else(|| format!("Rubric not found: {}", version))
+    }
+
+    pub fn list(&self) -> Result<RubricList, String> {
+        let store = self.store.lock().unwrap();
+        let rubrics = Self::rubrics(&store)?;
+        Ok(RubricList {
+            active_version: store
+                .active_version
+                .clone()
+                .unwrap_or_else(|| BASE_VERSION.to_string()),
+            rubrics,
+        })
+    }
+
+    pub fn get(&self, version: &str) -> Result<Rubric, String> {
+        Self::find(&self.store.lock().unwrap(), version)
+    }
+
+    /// Create or update a version; `v1` and pushed versions can no longer change
+    pub fn save(&self, mut rubric: Rubric) -> Result<Rubric, IpcError> {
+        rubric.validate()?;
+        if rubric.version == BASE_VERSION {
+            return Err(format!(
+                "Rubric {} is the original eval.yaml rubric; save the changes under a new version",
+                BASE_VERSION
+            )
+            .into());
+        }
+
+        let mut store = self.store.lock().unwrap();
+        if let Some(existing) = store.rubrics.iter().find(|r| r.version == rubric.version) {
+            if existing.pushed_at.is_some() {
+                return Err(format!(
+                    "Rubric {} was already pushed; save the changes under a new version",
+                    rubric.version
+                )
+                .into());
+            }
+        }
+
+        rubric.created_at = now_secs();
+        rubric.pushed_at = None;
+        store.rubrics.retain(|r| r.version != rubric.version);
+        store.rubrics.push(rubric.clone());
+        self.persist(&store)?;
+        Ok(rubric)
+    }
+
+    pub fn delete(&self, version: &str) -> Result<(), String> {
+        if version == BASE_VERSION {
+            return Err(format!("Rubric {} cannot be deleted", BASE_VERSION));
+        }
+        let mut store = self.store.lock().unwrap();
+        if store.active_version.as_deref() == Some(version) {
+            return Err(format!(
+                "Rubric {} is active and cannot be deleted",
+                version
+            ));
+        }
+        let before = store.rubrics.len();
+        store.rubrics.retain(|r| r.version != version);
+        if store.rubrics.len() == before {
+            return Err(format!("Rubric not found: {}", version));
+        }
+        self.persist(&store)
+    }
+
+    pub fn preview(
+        &self,
+        candidate: &Rubric,
+        evaluations: &[EvaluationRecord],
+    ) -> Result<RoutingPreview, IpcError> {
+        candidate.validate()?;
+        let store = self.store.lock().unwrap();
+        // Stores pushed to before `v1` was frozen fall back to the active rubric
+        let base = match Self::base(&store)? {
+            Some(base) => base,
+            None => Self::find(&store, store.active_version.as_deref().unwrap_or_default())?,
+        };
+        Ok(preview_routing(
+            candidate,
+            &store.rubrics,
+            &base,
+            evaluations,
+        ))
+    }
+
+    /// Write a saved rubric into eval.yaml and mark it active. The first push
+    /// freezes the rubric eval.yaml held until then as `v1`.
+    pub fn push(&self, version: &str) -> Result<PushResult, IpcError> {
+        let mut store = self.store.lock().unwrap();
+        let rubric = Self::find(&store, version)?;
+        rubric.validate()?;
+        let unfrozen = store.active_version.is_none()
+            && !store.rubrics.iter().any(|r| r.version == BASE_VERSION);
+        let original = if unfrozen { Self::base(&store)? } else { None };
+
+        let path = config_path(EvalConfig::FILE)
+            .ok_or_else(|| format!("Config file not found: {}", EvalConfig::FILE))?;
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        write_atomic(&path, apply_to_yaml(&raw, &rubric))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+        let pushed_at = now_secs();
+        if let Some(original) = original {
+            store.rubrics.insert(
+                0,
+                Rubric {
+                    created_at: pushed_at,
+                    pushed_at: Some(pushed_at),
+                    ..original
+                },
+            );
+        }
+        match store.rubrics.iter_mut().find(|r| r.version == version) {
+            Some(saved) => saved.pushed_at = Some(pushed_at),
+            None => store.rubrics.push(Rubric {
+                pushed_at: Some(pushed_at),
+                ..rubric
+            }),
+        }
+        store.active_version = Some(version.to_string());
+        self.persist(&store)?;
+
+        Ok(PushResult {
+            version: version.to_string(),
+            path: path.display().to_string(),
+            restart_required: true,
+        })
+    }
+}
+
+/// Tauri commands for managing evaluation rubrics
+#[tauri::command]
+pub async fn list_rubrics(manager: tauri::State<'_, RubricManager>) -> Result<RubricList, String> {
+    manager.list()
+}
+
+#[tauri::command]
+pub async fn get_rubric(
+    manager: tauri::State<'_, RubricManager>,
+    version: String,
+) -> Result<Rubric, String> {
+    manager.get(&version)
+}
+
+#[tauri::command]
+pub async fn save_rubric(
+    manager: tauri::State<'_, RubricManager>,
+    rubric: Rubric,
+) -> Result<Rubric, IpcError> {
+    manager.save(rubric)
+}
+
+#[tauri::command]
+pub async fn delete_rubric(
+    manager: tauri::State<'_, RubricManager>,
+    version: String,
+) -> Result<(), String> {
+    manager.delete(&version)
+}
+
+/// Preview how `rubric`'s thresholds would have re-routed recorded evaluations
+#[tauri::command]
+pub async fn preview_rubric_routing(
+    manager: tauri::State<'_, RubricManager>,
+    history: tauri::State<'_, EvolutionHistory>,
+    rubric: Rubric,
+    goal: Option<String>,
+    limit: Option<usize>,
+) -> Result<RoutingPreview, IpcError> {
+    let evaluations = history.evaluations(
+        goal.as_deref(),
+        None,
+        limit.unwrap_or(DEFAULT_PREVIEW_LIMIT),
+    )?;
+    manager.preview(&rubric, &evaluations)
+}
+
+#[tauri::command]
+pub async fn push_rubric(
+    manager: tauri::State<'_, RubricManager>,
+    version: String,
+) -> Result<PushResult, IpcError> {
+    manager.push(&version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_yaml() -> String {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../configs/eval.yaml");
+        std::fs::read_to_string(path).expect("configs/eval.yaml")
+    }
+
+    fn rubric(version: &str, tau_low: f64, tau_high: f64) -> Rubric {
+        Rubric {
+            version: version.to_string(),
+            weights: [("correctness", 0.6), ("safety", 0.4)]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+            tau_low,
+            tau_high,
+            description: String::new(),
+            created_at: 0.0,
+            pushed_at: None,
+        }
+    }
+
+    fn evaluation(
+        id: i64,
+        rubric_version: &str,
+        routing_path: &str,
+        score: f64,
+    ) -> EvaluationRecord {
+        EvaluationRecord {
+            id,
+            variant_id: None,
+            goal: "summarise".to_string(),
+            rubric_version: rubric_version.to_string(),
+            quality_score: score,
+            delta_score: 0.0,
+            robust_pct: 100.0,
+            cache_hit: false,
+            time_ms: 1.0,
+            routing_path: routing_path.to_string(),
+            violations: Vec::new(),
+            created_at: 0.0,
+        }
+    }
+
+    #[test]
+    fn validate_reports_each_bad_field() {
+        assert!(rubric("v2", 0.3, 0.8).validate().is_ok());
+
+        let mut bad = rubric("v 2", 0.9, 0.8);
+        bad.weights.insert("style".to_string(), 1.5);
+        let fields: Vec<String> = bad
+            .validate()
+            .unwrap_err()
+            .errors
+            .into_iter()
+            .map(|e| e.field)
+            .collect();
+        assert_eq!(
+            fields,
+            ["version", "weights.style", "weights", "tau_low"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn preview_reroutes_heuristic_scores_and_flags_uncertain_judgements() {
+        let base = rubric(BASE_VERSION, 0.25, 0.85);
+        let candidate = rubric("v2", 0.2, 0.7);
+        let evaluations = [
+            evaluation(1, BASE_VERSION, HEURISTIC_REJECT, 10.0),
+            // Heuristic score 0.22 now falls into the judge band
+            evaluation(2, BASE_VERSION, HEURISTIC_REJECT, 22.0),
+            // Judged somewhere in [0.25, 0.85]: still judged, or now accepted
+            evaluation(3, BASE_VERSION, LLM_JUDGE, 60.0),
+            evaluation(4, BASE_VERSION, "cached", 50.0),
+        ];
+
+        let preview = preview_routing(&candidate, &[], &base, &evaluations);
+
+        assert_eq!(preview.unchanged, 1);
+        assert_eq!(preview.changed, 1);
+        assert_eq!(preview.uncertain, 1);
+        assert_eq!(preview.skipped, 1);
+        assert_eq!(preview.transitions["heuristic_reject -> llm_judge"], 1);
+        assert_eq!(
+            preview.changes[1].possible_paths,
+            [LLM_JUDGE, HEURISTIC_ACCEPT]
+        );
+        assert_eq!(
+            (
+                preview.llm_judge_before,
+                preview.llm_judge_after_min,
+                preview.llm_judge_after_max
+            ),
+            (1, 1, 2)
+        );
+    }
+
+    #[test]
+    fn preview_judges_each_evaluation_against_its_own_rubric() {
+        let base = rubric(BASE_VERSION, 0.25, 0.85);
+        let narrow = rubric("v2", 0.5, 0.6);
+        let evaluations = [evaluation(1, "v2", LLM_JUDGE, 55.0)];
+
+        let preview = preview_routing(&narrow, std::slice::from_ref(&narrow), &base, &evaluations);
+
+        assert_eq!(preview.unchanged, 1);
+    }
+
+    #[test]
+    fn apply_to_yaml_round_trips_the_real_eval_config() {
+        let raw = eval_yaml();
+        let mut pushed = rubric("v2", 0.3, 0.8);
+        pushed.weights.insert("efficiency".to_string(), 0.0);
+        pushed.weights.insert("novelty".to_string(), 0.0);
+
+        let rewritten = apply_to_yaml(&raw, &pushed);
+        let config: EvalConfig = serde_yaml::from_str(&rewritten).unwrap();
+
+        assert_eq!((config.tau_low, config.tau_high), (0.3, 0.8));
+        assert_eq!(config.rubric_weights, pushed.weights);
+        assert!(rewritten.contains("TAU_LOW: 0.3      # Below this: reject without LLM"));
+        assert!(rewritten.contains("  correctness: 0.6       # Does it solve the task?"));
+        // Weights the rubric drops are removed; everything else is untouched
+        assert!(!rewritten.contains("faithfulness"));
+        for line in raw.lines().skip_while(|l| !l.starts_with("# LLM judge")) {
+            assert!(rewritten.contains(line), "lost line: {}", line);
+        }
+
+        let original: EvalConfig = serde_yaml::from_str(&raw).unwrap();
+        let restored = apply_to_yaml(&rewritten, &Rubric::from_config(&original));
+        let config: EvalConfig = serde_yaml::from_str(&restored).unwrap();
+        assert_eq!(config.rubric_weights, original.rubric_weights);
+        assert_eq!(
+            (config.tau_low, config.tau_high),
+            (original.tau_low, original.tau_high)
+        );
+    }
+
+    #[test]
+    fn first_push_freezes_the_original_eval_yaml_as_v1() {
+        let dir = std::env::temp_dir().join(format!("unity-rubrics-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(EvalConfig::FILE), eval_yaml()).unwrap();
+        std::env::set_var(crate::configs::CONFIG_DIR_ENV, &dir);
+
+        let manager = RubricManager {
+            path: None,
+            store: Arc::new(Mutex::new(RubricStore::default())),
+        };
+        let original = manager.get(BASE_VERSION).unwrap();
+        assert_eq!(original.pushed_at, None);
+        // Editing v1 before the first push would replace what gets frozen
+        assert!(manager.save(rubric(BASE_VERSION, 0.3, 0.8)).is_err());
+
+        manager.save(rubric("v2", 0.3, 0.8)).unwrap();
+        manager.push("v2").unwrap();
+
+        let list = manager.list().unwrap();
+        assert_eq!(list.active_version, "v2");
+        let frozen = manager.get(BASE_VERSION).unwrap();
+        assert_eq!(frozen.weights, original.weights);
+        assert_eq!(
+            (frozen.tau_low, frozen.tau_high),
+            (original.tau_low, original.tau_high)
+        );
+        assert!(frozen.pushed_at.is_some());
+        assert_eq!(
+            list.rubrics
+                .iter()
+                .filter(|r| r.version == BASE_VERSION)
+                .count(),
+            1
+        );
+        assert!(manager.save(rubric(BASE_VERSION, 0.1, 0.9)).is_err());
+        assert!(manager.delete(BASE_VERSION).is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
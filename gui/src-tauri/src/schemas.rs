@@ -0,0 +1,478 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// One schema rule broken by a payload, located by its dotted field path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A payload that does not match one of the contracts in `schemas/*.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaViolation {
+    /// Schema file the payload was checked against, e.g. `node_io.json`
+    pub schema: String,
+    /// Human-readable summary, for callers that only display errors
+    pub message: String,
+    pub errors: Vec<FieldError>,
+}
+
+impl SchemaViolation {
+    pub fn new(schema: &str, errors: Vec<FieldError>) -> Self {
+        let fields: Vec<String> = errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        Self {
+            schema: schema.to_string(),
+            message: format!("{} violation: {}", schema, fields.join("; ")),
+            errors,
+        }
+    }
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Error returned by commands that validate data crossing the IPC boundary.
+/// Serializes as a plain string or as a structured `SchemaViolation`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum IpcError {
+    Message(String),
+    Schema(SchemaViolation),
+}
+
+impl fmt::Display for IpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Message(message) => f.write_str(message),
+            Self::Schema(violation) => violation.fmt(f),
+        }
+    }
+}
+
+impl From<String> for IpcError {
+    fn from(message: String) -> Self {
+        Self::Message(message)
+    }
+}
+
+impl From<SchemaViolation> for IpcError {
+    fn from(violation: SchemaViolation) -> Self {
+        Self::Schema(violation)
+    }
+}
+
+/// Constraints that serde's type checks cannot express (ranges, patterns, lengths)
+pub trait Validate {
+    const SCHEMA: &'static str;
+
+    fn validate(&self, errors: &mut Vec<FieldError>);
+}
+
+fn push(errors: &mut Vec<FieldError>, field: &str, message: String) {
+    errors.push(FieldError::new(field, message));
+}
+
+fn check_range(errors: &mut Vec<FieldError>, field: &str, value: f64, min: f64, max: Option<f64>) {
+    if !value.is_finite() {
+        push(errors, field, "must be a finite number".to_string());
+    } else if value < min {
+        push(errors, field, format!("must be >= {}, got {}", min, value));
+    } else if let Some(max) = max.filter(|max| value > *max) {
+        push(errors, field, format!("must be <= {}, got {}", max, value));
+    }
+}
+
+fn check_opt_range(
+    errors: &mut Vec<FieldError>,
+    field: &str,
+    value: Option<f64>,
+    min: f64,
+    max: Option<f64>,
+) {
+    if let Some(value) = value {
+        check_range(errors, field, value, min, max);
+    }
+}
+
+/// `^[a-zA-Z0-9_-]+$`, used for task and node ids
+fn check_identifier(errors: &mut Vec<FieldError>, field: &str, value: &str) {
+    let valid = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        push(
+            errors,
+            field,
+            format!("must match ^[a-zA-Z0-9_-]+$, got {:?}", value),
+        );
+    }
+}
+
+/// Deserialize `value` into `T`, reporting the path of any missing or mistyped field
+pub fn decode<T: DeserializeOwned>(schema: &str, value: Value) -> Result<T, SchemaViolation> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let field = match e.path().to_string() {
+            path if path == "." => "(root)".to_string(),
+            path => path,
+        };
+        SchemaViolation::new(
+            schema,
+            vec![FieldError {
+                field,
+                message: e.into_inner().to_string(),
+            }],
+        )
+    })
+}
+
+/// Decode `value` as `T` and check its schema constraints
+pub fn parse<T: DeserializeOwned + Validate>(value: Value) -> Result<T, SchemaViolation> {
+    let parsed: T = decode(T::SCHEMA, value)?;
+    let mut errors = Vec::new();
+    parsed.validate(&mut errors);
+    if errors.is_empty() {
+        Ok(parsed)
+    } else {
+        Err(SchemaViolation::new(T::SCHEMA, errors))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// schemas/task_contract.json
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignedAgent {
+    LeadPlanner,
+    Research,
+    Code,
+    Evaluator,
+    Optimizer,
+    Adversary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskContract {
+    pub task_id: String,
+    pub description: String,
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+    pub assigned_agent: AssignedAgent,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_schema: Option<serde_json::Map<String, Value>>,
+}
+
+impl Validate for TaskContract {
+    const SCHEMA: &'static str = "task_contract.json";
+
+    fn validate(&self, errors: &mut Vec<FieldError>) {
+        check_identifier(errors, "task_id", &self.task_id);
+
+        let length = self.description.chars().count();
+        if !(10..=1000).contains(&length) {
+            push(
+                errors,
+                "description",
+                format!("must be 10 to 1000 characters, got {}", length),
+            );
+        }
+
+        check_opt_range(errors, "deadline", self.deadline, 0.0, None);
+    }
+}
+
+// ----------------------------------------------------------------------------
+// schemas/evaluation_result.json
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    Accept,
+    Reject,
+    HitlRequired,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correctness: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub faithfulness: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completeness: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safety: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub efficiency: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationResult {
+    pub task_id: String,
+    pub quality_score: f64,
+    pub decision: Decision,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<ScoreBreakdown>,
+    #[serde(default)]
+    pub violations: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub used_cache: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluation_time_ms: Option<f64>,
+}
+
+impl Validate for EvaluationResult {
+    const SCHEMA: &'static str = "evaluation_result.json";
+
+    fn validate(&self, errors: &mut Vec<FieldError>) {
+        check_range(
+            errors,
+            "quality_score",
+            self.quality_score,
+            0.0,
+            Some(100.0),
+        );
+        check_opt_range(
+            errors,
+            "evaluation_time_ms",
+            self.evaluation_time_ms,
+            0.0,
+            None,
+        );
+
+        if let Some(breakdown) = &self.score_breakdown {
+            let scores = [
+                ("correctness", breakdown.correctness),
+                ("faithfulness", breakdown.faithfulness),
+                ("completeness", breakdown.completeness),
+                ("safety", breakdown.safety),
+                ("efficiency", breakdown.efficiency),
+            ];
+            for (name, score) in scores {
+                let field = format!("score_breakdown.{}", name);
+                check_opt_range(errors, &field, score, 0.0, Some(100.0));
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// schemas/node_io.json
+// ----------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeType {
+    Evaluator,
+    Mutator,
+    Bandit,
+    Memory,
+    Workflow,
+    Telemetry,
+    Textgrad,
+    Aflow,
+    Mipro,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeInputs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workflow: Option<serde_json::Map<String, Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<serde_json::Map<String, Value>>,
+}
+
+/// Output contract shared by every node; backend responses are checked against it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeOutputs {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delta_score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub novelty: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub robust_pct: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_hit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violations: Option<Vec<String>>,
+}
+
+impl NodeOutputs {
+    fn check(&self, prefix: &str, errors: &mut Vec<FieldError>) {
+        let field = |name: &str| format!("{}{}", prefix, name);
+        check_opt_range(
+            errors,
+            &field("quality_score"),
+            self.quality_score,
+            0.0,
+            Some(100.0),
+        );
+        check_opt_range(errors, &field("novelty"), self.novelty, 0.0, Some(1.0));
+        check_opt_range(
+            errors,
+            &field("robust_pct"),
+            self.robust_pct,
+            0.0,
+            Some(100.0),
+        );
+        check_opt_range(errors, &field("time_ms"), self.time_ms, 0.0, None);
+        if let Some(delta) = self.delta_score.filter(|d| !d.is_finite()) {
+            push(
+                errors,
+                &field("delta_score"),
+                format!("must be a finite number, got {}", delta),
+            );
+        }
+    }
+}
+
+impl Validate for NodeOutputs {
+    const SCHEMA: &'static str = "node_io.json#/properties/outputs";
+
+    fn validate(&self, errors: &mut Vec<FieldError>) {
+        self.check("", errors);
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NodeMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeIo {
+    pub node_id: String,
+    pub node_type: NodeType,
+    pub inputs: NodeInputs,
+    pub outputs: NodeOutputs,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<NodeMetadata>,
+}
+
+impl Validate for NodeIo {
+    const SCHEMA: &'static str = "node_io.json";
+
+    fn validate(&self, errors: &mut Vec<FieldError>) {
+        check_identifier(errors, "node_id", &self.node_id);
+        if self.inputs.goal.as_deref() == Some("") {
+            push(errors, "inputs.goal", "must not be empty".to_string());
+        }
+        self.outputs.check("outputs.", errors);
+    }
+}
+
+/// Check a backend response against the node output contract, then decode it
+/// into the command's response type
+pub fn decode_node_outputs<T: DeserializeOwned>(value: Value) -> Result<T, SchemaViolation> {
+    parse::<NodeOutputs>(value.clone())?;
+    decode(NodeOutputs::SCHEMA, value)
+}
+
+/// Tauri commands for validating contracts from the frontend
+#[tauri::command]
+pub fn validate_task_contract(contract: Value) -> Result<TaskContract, IpcError> {
+    Ok(parse(contract)?)
+}
+
+#[tauri::command]
+pub fn validate_evaluation_result(result: Value) -> Result<EvaluationResult, IpcError> {
+    Ok(parse(result)?)
+}
+
+#[tauri::command]
+pub fn validate_node_io(node: Value) -> Result<NodeIo, IpcError> {
+    Ok(parse(node)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EvaluateResponse;
+    use serde_json::json;
+
+    fn evaluation(quality_score: Value) -> Value {
+        json!({
+            "quality_score": quality_score,
+            "delta_score": -1.5,
+            "robust_pct": 92.0,
+            "cache_hit": false,
+            "time_ms": 840.0,
+            "routing_path": "fast",
+            "violations": []
+        })
+    }
+
+    #[test]
+    fn decodes_a_response_that_meets_the_output_contract() {
+        let response: EvaluateResponse = decode_node_outputs(evaluation(json!(87.5))).unwrap();
+        assert_eq!(response.quality_score, 87.5);
+        assert_eq!(response.routing_path, "fast");
+        assert!(!response.stale);
+    }
+
+    #[test]
+    fn rejects_out_of_range_outputs_by_field() {
+        let violation =
+            decode_node_outputs::<EvaluateResponse>(evaluation(json!(140.0))).unwrap_err();
+        assert_eq!(violation.schema, NodeOutputs::SCHEMA);
+        assert_eq!(violation.errors.len(), 1);
+        assert_eq!(violation.errors[0].field, "quality_score");
+    }
+
+    #[test]
+    fn reports_the_path_of_a_mistyped_field() {
+        let violation =
+            decode_node_outputs::<EvaluateResponse>(evaluation(json!("high"))).unwrap_err();
+        assert_eq!(violation.errors[0].field, "quality_score");
+    }
+
+    #[test]
+    fn reports_fields_the_response_type_requires() {
+        let mut body = evaluation(json!(50.0));
+        body.as_object_mut().unwrap().remove("routing_path");
+
+        let violation = decode_node_outputs::<EvaluateResponse>(body).unwrap_err();
+        assert_eq!(violation.errors[0].field, "(root)");
+        assert!(
+            violation.message.contains("routing_path"),
+            "{}",
+            violation.message
+        );
+    }
+}
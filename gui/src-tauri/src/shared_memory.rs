@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Manager;
+use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
+
+use crate::util::now_secs;
+use crate::window_manager::{OfficeType, OfficeWindow, WindowManager};
+
+/// Append-only audit log (inside the app data dir)
+pub const AUDIT_FILE: &str = "memory_audit.jsonl";
+
+/// Number of audit entries kept in memory for the Security office
+const AUDIT_RING_SIZE: usize = 1000;
+
+/// How often expired grants are swept
+const EXPIRY_SWEEP_INTERVAL_SECS: u64 = 5;
+
+/// Payload of a `request_memory_access` event from an office window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryAccessRequest {
+    /// Memory namespace or key being requested
+    pub scope: String,
+    /// Window whose memory is being shared, if the request targets another office
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default)]
+    pub purpose: Option<String>,
+}
+
+/// A time-limited permission for one window to read a memory scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryGrant {
+    pub id: String,
+    pub window_id: String,
+    pub office_type: OfficeType,
+    pub scope: String,
+    pub owner: Option<String>,
+    pub granted_at: f64,
+    pub expires_at: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Granted,
+    Denied,
+    Expired,
+    Revoked,
+}
+
+/// One line of the memory access audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: f64,
+    pub action: AuditAction,
+    pub window_id: String,
+    pub office_type: Option<OfficeType>,
+    pub scope: String,
+    pub grant_id: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Consent-enforcing gatekeeper for cross-office memory access
+#[derive(Clone)]
+pub struct SharedMemory {
+    app_handle: tauri::AppHandle,
+    windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    grants: Arc<Mutex<HashMap<String, MemoryGrant>>>,
+    audit: Arc<Mutex<VecDeque<AuditEntry>>>,
+    audit_path: Option<PathBuf>,
+}
+
+impl SharedMemory {
+    pub fn new(
+        app_handle: tauri::AppHandle,
+        windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    ) -> Self {
+        let audit_path = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(AUDIT_FILE));
+
+        // Restore the tail of the trail so the Security office sees earlier sessions
+        let audit = audit_path
+            .as_deref()
+            .map(Self::load_audit_tail)
+            .unwrap_or_default();
+
+        Self {
+            app_handle,
+            windows,
+            grants: Arc::new(Mutex::new(HashMap::new())),
+            audit: Arc::new(Mutex::new(audit)),
+            audit_path,
+        }
+    }
+
+    /// Last `AUDIT_RING_SIZE` parseable entries of the audit log
+    fn load_audit_tail(path: &Path) -> VecDeque<AuditEntry> {
+        let mut audit = VecDeque::new();
+        let Ok(file) = std::fs::File::open(path) else {
+            return audit;
+        };
+
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<AuditEntry>(&line) {
+                if audit.len() >= AUDIT_RING_SIZE {
+                    audit.pop_front();
+                }
+                audit.push_back(entry);
+            }
+        }
+        audit
+    }
+
+    /// Grant or deny a memory access request from `window_id`
+    pub async fn request_access(
+        &self,
+        window_id: &str,
+        request: MemoryAccessRequest,
+    ) -> Result<MemoryGrant, String> {
+        let decision = {
+            let windows = self.windows.read().await;
+            Self::decide(&windows, window_id, &request)
+        };
+
+        match decision {
+            Ok((ttl, office_type)) => {
+                let granted_at = now_secs();
+                let grant = MemoryGrant {
+                    id: Uuid::new_v4().to_string(),
+                    window_id: window_id.to_string(),
+                    office_type,
+                    scope: request.scope.clone(),
+                    owner: request.owner.clone(),
+                    granted_at,
+                    expires_at: granted_at + ttl as f64,
+                };
+
+                self.grants
+                    .lock()
+                    .await
+                    .insert(grant.id.clone(), grant.clone());
+
+                self.record(AuditEntry {
+                    timestamp: granted_at,
+                    action: AuditAction::Granted,
+                    window_id: window_id.to_string(),
+                    office_type: Some(grant.office_type.clone()),
+                    scope: grant.scope.clone(),
+                    grant_id: Some(grant.id.clone()),
+                    reason: request.purpose,
+                })
+                .await;
+
+                Ok(grant)
+            }
+            Err(reason) => {
+                let office_type = self
+                    .windows
+                    .read()
+                    .await
+                    .get(window_id)
+                    .map(|w| w.office_type.clone());
+
+                self.record(AuditEntry {
+                    timestamp: now_secs(),
+                    action: AuditAction::Denied,
+                    window_id: window_id.to_string(),
+                    office_type,
+                    scope: request.scope,
+                    grant_id: None,
+                    reason: Some(reason.clone()),
+                })
+                .await;
+
+                Err(reason)
+            }
+        }
+    }
+
+    /// Consent rules: both requester and owner must have opted in; the
+    /// shorter of their TTLs bounds the grant
+    fn decide(
+        windows: &HashMap<String, OfficeWindow>,
+        window_id: &str,
+        request: &MemoryAccessRequest,
+    ) -> Result<(u64, OfficeType), String> {
+        if request.scope.trim().is_empty() {
+            return Err("Memory scope cannot be empty".to_string());
+        }
+
+        let requester = windows
+            .get(window_id)
+            .ok_or_else(|| format!("Unknown office window: {}", window_id))?;
+        if !requester.memory_consent {
+            return Err(format!(
+                "{} has not consented to shared memory",
+                requester.office_type
+            ));
+        }
+
+        let mut ttl = requester.shared_memory_ttl;
+        if let Some(owner_id) = &request.owner {
+            let owner = windows
+                .get(owner_id)
+                .ok_or_else(|| format!("Unknown memory owner: {}", owner_id))?;
+            if !owner.memory_consent {
+                return Err(format!(
+                    "{} has not consented to share its memory",
+                    owner.office_type
+                ));
+            }
+            ttl = ttl.min(owner.shared_memory_ttl);
+        }
+
+        if ttl == 0 {
+            return Err("Shared memory TTL is zero".to_string());
+        }
+
+        Ok((ttl, requester.office_type.clone()))
+    }
+
+    /// Whether a grant is still live
+    pub async fn is_granted(&self, grant_id: &str) -> bool {
+        self.grants
+            .lock()
+            .await
+            .get(grant_id)
+            .map(|g| g.expires_at > now_secs())
+            .unwrap_or(false)
+    }
+
+    pub async fn active_grants(&self) -> Vec<MemoryGrant> {
+        let now = now_secs();
+        self.grants
+            .lock()
+            .await
+            .values()
+            .filter(|g| g.expires_at > now)
+            .cloned()
+            .collect()
+    }
+
+    /// Recompute the expiry of live grants held by or over `window_id` after its
+    /// TTL changed; grants whose new expiry has already passed are expired now
+    pub async fn apply_ttl(&self, window_id: &str) {
+        let updated: Vec<MemoryGrant> = {
+            let windows = self.windows.read().await;
+            let mut grants = self.grants.lock().await;
+            grants
+                .values_mut()
+                .filter(|g| g.window_id == window_id || g.owner.as_deref() == Some(window_id))
+                .filter_map(|grant| {
+                    let requester = windows.get(&grant.window_id)?;
+                    let ttl = match grant.owner.as_ref().and_then(|id| windows.get(id)) {
+                        Some(owner) => requester.shared_memory_ttl.min(owner.shared_memory_ttl),
+                        None => requester.shared_memory_ttl,
+                    };
+                    grant.expires_at = grant.granted_at + ttl as f64;
+                    Some(grant.clone())
+                })
+                .collect()
+        };
+
+        for grant in &updated {
+            self.notify(&grant.window_id, "memory_access_updated", grant);
+        }
+        self.expire_grants().await;
+    }
+
+    /// Revoke every grant held by or over `window_id` (consent withdrawn or window closed)
+    pub async fn revoke_window(&self, window_id: &str, reason: &str) {
+        let revoked: Vec<MemoryGrant> = {
+            let mut grants = self.grants.lock().await;
+            let ids: Vec<String> = grants
+                .values()
+                .filter(|g| g.window_id == window_id || g.owner.as_deref() == Some(window_id))
+                .map(|g| g.id.clone())
+                .collect();
+            ids.iter().filter_map(|id| grants.remove(id)).collect()
+        };
+
+        for grant in revoked {
+            self.notify(&grant.window_id, "memory_access_revoked", &grant);
+            self.record(AuditEntry {
+                timestamp: now_secs(),
+                action: AuditAction::Revoked,
+                window_id: grant.window_id,
+                office_type: Some(grant.office_type),
+                scope: grant.scope,
+                grant_id: Some(grant.id),
+                reason: Some(reason.to_string()),
+            })
+            .await;
+        }
+    }
+
+    /// Drop grants whose TTL has elapsed
+    pub async fn expire_grants(&self) {
+        let now = now_secs();
+        let expired: Vec<MemoryGrant> = {
+            let mut grants = self.grants.lock().await;
+            let ids: Vec<String> = grants
+                .values()
+                .filter(|g| g.expires_at <= now)
+                .map(|g| g.id.clone())
+                .collect();
+            ids.iter().filter_map(|id| grants.remove(id)).collect()
+        };
+
+        for grant in expired {
+            self.notify(&grant.window_id, "memory_access_expired", &grant);
+            self.record(AuditEntry {
+                timestamp: now,
+                action: AuditAction::Expired,
+                window_id: grant.window_id,
+                office_type: Some(grant.office_type),
+                scope: grant.scope,
+                grant_id: Some(grant.id),
+                reason: None,
+            })
+            .await;
+        }
+    }
+
+    /// Periodically expire grants in the background
+    pub fn spawn_expiry_sweeper(&self) {
+        let memory = self.clone();
+        tauri::async_runtime::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(EXPIRY_SWEEP_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                memory.expire_grants().await;
+            }
+        });
+    }
+
+    pub async fn audit_trail(&self, limit: Option<usize>) -> Vec<AuditEntry> {
+        let audit = self.audit.lock().await;
+        let limit = limit.unwrap_or(audit.len());
+        audit.iter().rev().take(limit).cloned().collect()
+    }
+
+    fn notify<S: Serialize + Clone>(&self, window_id: &str, event: &str, payload: &S) {
+        if let Some(window) = self.app_handle.get_window(window_id) {
+            window.emit(event, payload.clone()).ok();
+        }
+    }
+
+    /// Append to the audit ring and log file, and stream the entry to Security offices
+    async fn record(&self, entry: AuditEntry) {
+        if let Some(path) = &self.audit_path {
+            if let Err(e) = Self::append_to_log(path, &entry) {
+                eprintln!("[Unity] Failed to write memory audit log: {}", e);
+            }
+        }
+
+        let security_windows: Vec<String> = self
+            .windows
+            .read()
+            .await
+            .values()
+            .filter(|w| w.office_type == OfficeType::Security)
+            .map(|w| w.id.clone())
+            .collect();
+        for window_id in security_windows {
+            self.notify(&window_id, "memory_audit_event", &entry);
+        }
+
+        let mut audit = self.audit.lock().await;
+        if audit.len() >= AUDIT_RING_SIZE {
+            audit.pop_front();
+        }
+        audit.push_back(entry);
+    }
+
+    fn append_to_log(path: &PathBuf, entry: &AuditEntry) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        let line = serde_json::to_string(entry)?;
+        writeln!(file, "{}", line)
+    }
+}
+
+/// Tauri commands for shared memory access
+#[tauri::command]
+pub async fn get_memory_audit(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    limit: Option<usize>,
+) -> Result<Vec<AuditEntry>, String> {
+    let manager = state.read().await;
+    Ok(manager.shared_memory().audit_trail(limit).await)
+}
+
+#[tauri::command]
+pub async fn get_memory_grants(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Vec<MemoryGrant>, String> {
+    let manager = state.read().await;
+    Ok(manager.shared_memory().active_grants().await)
+}
+
+#[tauri::command]
+pub async fn check_memory_grant(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    grant_id: String,
+) -> Result<bool, String> {
+    let manager = state.read().await;
+    Ok(manager.shared_memory().is_granted(&grant_id).await)
+}
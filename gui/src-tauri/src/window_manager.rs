@@ -1,10 +1,21 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
-use tauri::{Manager, Window, WindowBuilder, WindowUrl};
-use tokio::sync::RwLock;
+use tauri::{Manager, Window, WindowBuilder, WindowEvent, WindowUrl};
+use tokio::sync::{Mutex, MutexGuard, RwLock};
 use uuid::Uuid;
 
+use crate::message_bus::{MessageBus, OfficeMessage};
+use crate::office_layout::{
+    read_window_geometry, LayoutStore, OfficeLayout, OfficeLayoutEntry, SessionRecorder,
+    LAYOUT_FILE,
+};
+use crate::shared_memory::{MemoryAccessRequest, SharedMemory};
+use crate::util::now_secs;
+use crate::workflow_library::WorkflowLibrary;
+use crate::workflow_runs::WorkflowRuns;
+
 /// Represents a Unity Office window instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OfficeWindow {
@@ -13,10 +24,53 @@ pub struct OfficeWindow {
     pub title: String,
     pub position: Option<(i32, i32)>,
     pub size: (u32, u32),
+    #[serde(default)]
+    pub monitor: Option<String>,
     pub memory_consent: bool,
     pub shared_memory_ttl: u64, // in seconds
+    #[serde(default)]
+    pub opened_at: f64,
+    #[serde(default)]
+    pub focused: bool,
+}
+
+/// How many windows of one office type may be open at once
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum InstancePolicy {
+    /// One window; opening it again is an error
+    Singleton,
+    /// One window; opening it again focuses the existing one
+    Focus,
+    /// Up to `max` windows; beyond that the most recent one is focused
+    Multiple { max: usize },
+}
+
+/// Result of asking for an office window under its instance policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenedOffice {
+    pub window_id: String,
+    /// False when an existing window was focused instead
+    pub created: bool,
+}
+
+/// Open window count for one office type, as reported by `get_offices`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeInstances {
+    pub office_type: OfficeType,
+    pub count: usize,
+    pub policy: InstancePolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfficeInventory {
+    pub windows: Vec<OfficeWindow>,
+    pub instances: Vec<OfficeInstances>,
 }
 
+/// Label of the main Unity window, which receives office lifecycle events
+const MAIN_WINDOW: &str = "main";
+
 /// All 43 office types in Unity
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum OfficeType {
@@ -85,54 +139,57 @@ pub enum OfficeType {
     EmergencyResponse,
 }
 
-impl OfficeType {
-    pub fn to_string(&self) -> String {
-        match self {
-            Self::Orchestrator => "Orchestrator".to_string(),
-            Self::Memory => "Memory Graph".to_string(),
-            Self::Security => "Security Office".to_string(),
-            Self::TradingOffice => "Trading Office".to_string(),
-            Self::CryptoOffice => "Crypto Office".to_string(),
-            Self::TaxAdvisor => "Tax Advisor".to_string(),
-            Self::FinancialAdvisor => "Financial Advisor".to_string(),
-            Self::BankingOffice => "Banking Office".to_string(),
-            Self::LegalOffice => "Legal Office".to_string(),
-            Self::ComplianceOfficer => "Compliance Officer".to_string(),
-            Self::ContractAnalyst => "Contract Analyst".to_string(),
-            Self::IntellectualProperty => "IP Office".to_string(),
-            Self::TravelPlanner => "Travel Planner".to_string(),
-            Self::RestaurantConcierge => "Restaurant Concierge".to_string(),
-            Self::EventCoordinator => "Event Coordinator".to_string(),
-            Self::PersonalShopper => "Personal Shopper".to_string(),
-            Self::PhysicalTrainer => "Physical Trainer".to_string(),
-            Self::Nutritionist => "Nutritionist".to_string(),
-            Self::SleepCoach => "Sleep Coach".to_string(),
-            Self::Psychologist => "Psychologist".to_string(),
-            Self::MedicalAdvisor => "Medical Advisor".to_string(),
-            Self::ContentCreator => "Content Creator".to_string(),
-            Self::VideoEditor => "Video Editor".to_string(),
-            Self::GraphicDesigner => "Graphic Designer".to_string(),
-            Self::MusicProducer => "Music Producer".to_string(),
-            Self::DevOpsEngineer => "DevOps Engineer".to_string(),
-            Self::DataAnalyst => "Data Analyst".to_string(),
-            Self::SecurityAnalyst => "Security Analyst".to_string(),
-            Self::CloudArchitect => "Cloud Architect".to_string(),
-            Self::ResearchAnalyst => "Research Analyst".to_string(),
-            Self::EducationAdvisor => "Education Advisor".to_string(),
-            Self::LanguageTutor => "Language Tutor".to_string(),
-            Self::SkillCoach => "Skill Coach".to_string(),
-            Self::TarotReader => "Tarot Reader".to_string(),
-            Self::Astrologer => "Astrologer".to_string(),
-            Self::MeditationGuide => "Meditation Guide".to_string(),
-            Self::LifeCoach => "Life Coach".to_string(),
-            Self::KitchenManager => "Kitchen Manager".to_string(),
-            Self::HomeAutomation => "Home Automation".to_string(),
-            Self::MaintenanceScheduler => "Maintenance Scheduler".to_string(),
-            Self::QuantumComputing => "Quantum Computing".to_string(),
-            Self::EmergencyResponse => "Emergency Response".to_string(),
-        }
+impl fmt::Display for OfficeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Orchestrator => "Orchestrator",
+            Self::Memory => "Memory Graph",
+            Self::Security => "Security Office",
+            Self::TradingOffice => "Trading Office",
+            Self::CryptoOffice => "Crypto Office",
+            Self::TaxAdvisor => "Tax Advisor",
+            Self::FinancialAdvisor => "Financial Advisor",
+            Self::BankingOffice => "Banking Office",
+            Self::LegalOffice => "Legal Office",
+            Self::ComplianceOfficer => "Compliance Officer",
+            Self::ContractAnalyst => "Contract Analyst",
+            Self::IntellectualProperty => "IP Office",
+            Self::TravelPlanner => "Travel Planner",
+            Self::RestaurantConcierge => "Restaurant Concierge",
+            Self::EventCoordinator => "Event Coordinator",
+            Self::PersonalShopper => "Personal Shopper",
+            Self::PhysicalTrainer => "Physical Trainer",
+            Self::Nutritionist => "Nutritionist",
+            Self::SleepCoach => "Sleep Coach",
+            Self::Psychologist => "Psychologist",
+            Self::MedicalAdvisor => "Medical Advisor",
+            Self::ContentCreator => "Content Creator",
+            Self::VideoEditor => "Video Editor",
+            Self::GraphicDesigner => "Graphic Designer",
+            Self::MusicProducer => "Music Producer",
+            Self::DevOpsEngineer => "DevOps Engineer",
+            Self::DataAnalyst => "Data Analyst",
+            Self::SecurityAnalyst => "Security Analyst",
+            Self::CloudArchitect => "Cloud Architect",
+            Self::ResearchAnalyst => "Research Analyst",
+            Self::EducationAdvisor => "Education Advisor",
+            Self::LanguageTutor => "Language Tutor",
+            Self::SkillCoach => "Skill Coach",
+            Self::TarotReader => "Tarot Reader",
+            Self::Astrologer => "Astrologer",
+            Self::MeditationGuide => "Meditation Guide",
+            Self::LifeCoach => "Life Coach",
+            Self::KitchenManager => "Kitchen Manager",
+            Self::HomeAutomation => "Home Automation",
+            Self::MaintenanceScheduler => "Maintenance Scheduler",
+            Self::QuantumComputing => "Quantum Computing",
+            Self::EmergencyResponse => "Emergency Response",
+        };
+        f.write_str(name)
     }
+}
 
+impl OfficeType {
     pub fn get_default_size(&self) -> (u32, u32) {
         match self {
             Self::Orchestrator => (1400, 900),
@@ -152,60 +209,307 @@ impl OfficeType {
             _ => format!("{}/office/{:?}", base_url, self).to_lowercase(),
         }
     }
+
+    /// Instance policy used unless overridden at runtime
+    pub fn default_instance_policy(&self) -> InstancePolicy {
+        match self {
+            Self::EmergencyResponse => InstancePolicy::Singleton,
+            Self::TradingOffice
+            | Self::CryptoOffice
+            | Self::DataAnalyst
+            | Self::ResearchAnalyst
+            | Self::ContentCreator => InstancePolicy::Multiple { max: 3 },
+            _ => InstancePolicy::Focus,
+        }
+    }
+}
+
+/// Keeps the registry in step with windows the user opens, moves and closes
+#[derive(Clone)]
+struct OfficeTracker {
+    windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    session: SessionRecorder,
+    memory: SharedMemory,
+    app_handle: tauri::AppHandle,
+}
+
+impl OfficeTracker {
+    /// Drop a window from the registry, however it was closed; later calls are no-ops
+    async fn forget(&self, window_id: &str) -> Option<OfficeWindow> {
+        let removed = self.windows.write().await.remove(window_id)?;
+
+        self.memory.revoke_window(window_id, "office closed").await;
+        self.session.schedule();
+        self.notify_main("unity:office_closed", &removed);
+
+        Some(removed)
+    }
+
+    fn notify_main(&self, event: &str, office_window: &OfficeWindow) {
+        if let Some(main) = self.app_handle.get_window(MAIN_WINDOW) {
+            main.emit(event, office_window).ok();
+        }
+    }
+
+    fn on_window_event(&self, window: &Window, event: &WindowEvent) {
+        let tracker = self.clone();
+        let window_id = window.label().to_string();
+
+        match event {
+            // Capture the final geometry too, so a closed office reopens where it was
+            WindowEvent::Moved(_)
+            | WindowEvent::Resized(_)
+            | WindowEvent::CloseRequested { .. } => {
+                let Some(geometry) = read_window_geometry(window) else {
+                    return;
+                };
+                tauri::async_runtime::spawn(async move {
+                    if let Some(office_window) = tracker.windows.write().await.get_mut(&window_id) {
+                        office_window.position = Some(geometry.position);
+                        office_window.size = geometry.size;
+                        office_window.monitor = geometry.monitor;
+                    }
+                    tracker.session.schedule();
+                });
+            }
+            WindowEvent::Focused(focused) => {
+                let focused = *focused;
+                tauri::async_runtime::spawn(async move {
+                    if let Some(office_window) = tracker.windows.write().await.get_mut(&window_id) {
+                        office_window.focused = focused;
+                    }
+                });
+            }
+            WindowEvent::Destroyed => {
+                tauri::async_runtime::spawn(async move {
+                    tracker.forget(&window_id).await;
+                });
+            }
+            _ => {}
+        }
+    }
 }
 
 /// Manages all Unity office windows
 pub struct WindowManager {
     windows: Arc<RwLock<HashMap<String, OfficeWindow>>>,
+    layouts: Arc<RwLock<LayoutStore>>,
+    session: SessionRecorder,
+    bus: MessageBus,
+    memory: SharedMemory,
+    runs: WorkflowRuns,
+    library: WorkflowLibrary,
+    tracker: OfficeTracker,
+    policies: RwLock<HashMap<OfficeType, InstancePolicy>>,
+    /// Serializes the policy check with window creation
+    open_lock: Mutex<()>,
     app_handle: tauri::AppHandle,
 }
 
 impl WindowManager {
     pub fn new(app_handle: tauri::AppHandle) -> Self {
+        let windows = Arc::new(RwLock::new(HashMap::new()));
+        let layout_path = app_handle
+            .path_resolver()
+            .app_data_dir()
+            .map(|dir| dir.join(LAYOUT_FILE));
+        let layouts = Arc::new(RwLock::new(LayoutStore::load(layout_path)));
+
+        let memory = SharedMemory::new(app_handle.clone(), windows.clone());
+        memory.spawn_expiry_sweeper();
+
+        let session = SessionRecorder::new(windows.clone(), layouts.clone());
+        let tracker = OfficeTracker {
+            windows: windows.clone(),
+            session: session.clone(),
+            memory: memory.clone(),
+            app_handle: app_handle.clone(),
+        };
+
         Self {
-            windows: Arc::new(RwLock::new(HashMap::new())),
+            session,
+            bus: MessageBus::new(app_handle.clone(), windows.clone()),
+            memory,
+            tracker,
+            runs: WorkflowRuns::new(app_handle.clone()),
+            library: WorkflowLibrary::new(&app_handle),
+            policies: RwLock::new(HashMap::new()),
+            open_lock: Mutex::new(()),
+            windows,
+            layouts,
             app_handle,
         }
     }
 
-    /// Create a new office window
+    /// Open an office window (or focus an existing one) according to its
+    /// instance policy, returning the window id
     pub async fn create_office_window(
         &self,
         office_type: OfficeType,
         memory_consent: bool,
         shared_memory_ttl: Option<u64>,
+    ) -> Result<String, String> {
+        self.open_office(office_type, memory_consent, shared_memory_ttl, None)
+            .await
+            .map(|opened| opened.window_id)
+    }
+
+    /// Apply the office's instance policy, creating a window only if it allows one
+    pub async fn open_office(
+        &self,
+        office_type: OfficeType,
+        memory_consent: bool,
+        shared_memory_ttl: Option<u64>,
+        placement: Option<&OfficeLayoutEntry>,
+    ) -> Result<OpenedOffice, String> {
+        let _guard = self.lock_opening().await;
+        self.open_office_locked(office_type, memory_consent, shared_memory_ttl, placement)
+            .await
+    }
+
+    /// Serialize window creation. Callers that inspect the open windows before
+    /// deciding to open one hold this across both and use `open_office_locked`.
+    pub async fn lock_opening(&self) -> MutexGuard<'_, ()> {
+        self.open_lock.lock().await
+    }
+
+    /// `open_office` for callers already holding `lock_opening`
+    pub async fn open_office_locked(
+        &self,
+        office_type: OfficeType,
+        memory_consent: bool,
+        shared_memory_ttl: Option<u64>,
+        placement: Option<&OfficeLayoutEntry>,
+    ) -> Result<OpenedOffice, String> {
+        let existing = self.find_office_windows(&office_type).await;
+        let focus_target = match self.instance_policy(&office_type).await {
+            InstancePolicy::Singleton if !existing.is_empty() => {
+                return Err(format!("{} is already open", office_type));
+            }
+            InstancePolicy::Focus => existing.last(),
+            InstancePolicy::Multiple { max } if existing.len() >= max.max(1) => existing.last(),
+            _ => None,
+        };
+
+        if let Some(window_id) = focus_target {
+            if let Some(window) = self.app_handle.get_window(window_id) {
+                window
+                    .set_focus()
+                    .map_err(|e| format!("Failed to focus window: {}", e))?;
+            }
+            return Ok(OpenedOffice {
+                window_id: window_id.clone(),
+                created: false,
+            });
+        }
+
+        let window_id = self
+            .build_office_window(office_type, memory_consent, shared_memory_ttl, placement)
+            .await?;
+        Ok(OpenedOffice {
+            window_id,
+            created: true,
+        })
+    }
+
+    /// Effective instance policy for an office type
+    pub async fn instance_policy(&self, office_type: &OfficeType) -> InstancePolicy {
+        self.policies
+            .read()
+            .await
+            .get(office_type)
+            .copied()
+            .unwrap_or_else(|| office_type.default_instance_policy())
+    }
+
+    /// Override an office type's instance policy, or restore its default with `None`
+    pub async fn set_instance_policy(
+        &self,
+        office_type: OfficeType,
+        policy: Option<InstancePolicy>,
+    ) -> Result<(), String> {
+        let mut policies = self.policies.write().await;
+        match policy {
+            Some(InstancePolicy::Multiple { max: 0 }) => {
+                return Err("Multiple instance policy needs max of at least 1".to_string());
+            }
+            Some(policy) => {
+                policies.insert(office_type, policy);
+            }
+            None => {
+                policies.remove(&office_type);
+            }
+        }
+        Ok(())
+    }
+
+    /// Create an office window, optionally placed where a saved layout left it
+    async fn build_office_window(
+        &self,
+        office_type: OfficeType,
+        memory_consent: bool,
+        shared_memory_ttl: Option<u64>,
+        placement: Option<&OfficeLayoutEntry>,
     ) -> Result<String, String> {
         let window_id = format!("office_{}", Uuid::new_v4());
-        let title = format!("Unity — {}", office_type.to_string());
-        let size = office_type.get_default_size();
+        let title = format!("Unity — {}", office_type);
+        let size = placement
+            .map(|p| p.size)
+            .unwrap_or_else(|| office_type.get_default_size());
         let url = office_type.get_url();
 
         // Create the actual Tauri window
-        let window = WindowBuilder::new(
-            &self.app_handle,
-            &window_id,
-            WindowUrl::App(url.into()),
-        )
-        .title(&title)
-        .inner_size(size.0 as f64, size.1 as f64)
-        .resizable(true)
-        .center()
+        let builder = WindowBuilder::new(&self.app_handle, &window_id, WindowUrl::App(url.into()))
+            .title(&title)
+            .inner_size(size.0 as f64, size.1 as f64)
+            .resizable(true);
+
+        let window = match placement.and_then(|p| p.position) {
+            Some((x, y)) => builder.position(x as f64, y as f64),
+            None => builder.center(),
+        }
         .build()
         .map_err(|e| format!("Failed to create window: {}", e))?;
 
+        // A saved position on a monitor that is no longer attached would leave
+        // the window off-screen, so fall back to centering it
+        if let Some(saved_monitor) = placement.and_then(|p| p.monitor.as_ref()) {
+            let current = window
+                .current_monitor()
+                .ok()
+                .flatten()
+                .and_then(|m| m.name().cloned());
+            if current.as_ref() != Some(saved_monitor) {
+                window.center().ok();
+            }
+        }
+
+        let geometry = read_window_geometry(&window);
+
         // Store window metadata
         let office_window = OfficeWindow {
             id: window_id.clone(),
             office_type: office_type.clone(),
             title,
-            position: None,
+            position: geometry.as_ref().map(|g| g.position),
             size,
+            monitor: geometry.and_then(|g| g.monitor),
             memory_consent,
             shared_memory_ttl: shared_memory_ttl.unwrap_or(3600), // Default 1 hour TTL
+            opened_at: now_secs(),
+            focused: false,
         };
 
         let mut windows = self.windows.write().await;
-        windows.insert(window_id.clone(), office_window);
+        windows.insert(window_id.clone(), office_window.clone());
+        drop(windows);
+
+        let tracker = self.tracker.clone();
+        let tracked = window.clone();
+        window.on_window_event(move |event| tracker.on_window_event(&tracked, event));
+        self.session.schedule();
+        self.tracker
+            .notify_main("unity:office_opened", &office_window);
 
         // Set up IPC handlers for this window
         self.setup_window_ipc(&window, office_type).await?;
@@ -219,37 +523,123 @@ impl WindowManager {
         window: &Window,
         office_type: OfficeType,
     ) -> Result<(), String> {
-        let window_clone = window.clone();
-
-        // Listen for memory sharing requests
+        // Memory sharing requests are checked against consent and TTL
+        let memory = self.memory.clone();
+        let requester = window.clone();
         window.listen("request_memory_access", move |event| {
-            println!(
-                "Office {:?} requesting memory access: {:?}",
-                office_type, event.payload()
-            );
+            let parsed = event
+                .payload()
+                .ok_or_else(|| "Empty memory access request".to_string())
+                .and_then(|raw| {
+                    serde_json::from_str::<MemoryAccessRequest>(raw)
+                        .map_err(|e| format!("Malformed memory access request: {}", e))
+                });
+
+            let memory = memory.clone();
+            let requester = requester.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match parsed {
+                    Ok(request) => memory.request_access(requester.label(), request).await,
+                    Err(e) => Err(e),
+                };
+
+                match result {
+                    Ok(grant) => requester.emit("memory_access_granted", grant).ok(),
+                    Err(reason) => requester.emit("memory_access_denied", reason).ok(),
+                };
+            });
         });
 
-        // Listen for inter-office messages
-        let window_clone2 = window.clone();
+        // Route inter-office messages through the bus, stamping the sender
+        let bus = self.bus.clone();
+        let sender = window.clone();
         window.listen("inter_office_message", move |event| {
-            println!(
-                "Inter-office message for {:?}: {:?}",
-                office_type, event.payload()
-            );
+            let parsed = event
+                .payload()
+                .ok_or_else(|| "Empty inter-office message".to_string())
+                .and_then(|raw| {
+                    serde_json::from_str::<OfficeMessage>(raw)
+                        .map_err(|e| format!("Malformed inter-office message: {}", e))
+                });
+
+            let bus = bus.clone();
+            let sender = sender.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match parsed {
+                    Ok(mut message) => {
+                        message.from = Some(sender.label().to_string());
+                        bus.send(message).await.map(|_| ())
+                    }
+                    Err(e) => Err(e),
+                };
+
+                if let Err(e) = result {
+                    sender.emit("office_message_error", e).ok();
+                }
+            });
+        });
+
+        // Offices announce readiness once their frontend can receive events;
+        // that is when messages queued while they were closed get delivered
+        let bus = self.bus.clone();
+        let ready_window = window.clone();
+        window.listen("office_ready", move |_| {
+            let bus = bus.clone();
+            let office_type = office_type.clone();
+            let window_id = ready_window.label().to_string();
+            tauri::async_runtime::spawn(async move {
+                bus.flush_queue(&office_type, &window_id).await;
+            });
         });
 
         Ok(())
     }
 
+    /// Ids of every open window of the given office type, oldest first
+    pub async fn find_office_windows(&self, office_type: &OfficeType) -> Vec<String> {
+        let windows = self.windows.read().await;
+        let mut matching: Vec<&OfficeWindow> = windows
+            .values()
+            .filter(|w| w.office_type == *office_type)
+            .collect();
+        matching.sort_by(|a, b| a.opened_at.total_cmp(&b.opened_at));
+        matching.into_iter().map(|w| w.id.clone()).collect()
+    }
+
+    pub fn app_handle(&self) -> &tauri::AppHandle {
+        &self.app_handle
+    }
+
+    /// The broker used for inter-office messaging
+    pub fn message_bus(&self) -> &MessageBus {
+        &self.bus
+    }
+
+    /// The consent-enforcing shared memory layer
+    pub fn shared_memory(&self) -> &SharedMemory {
+        &self.memory
+    }
+
+    /// Registry of background workflow runs
+    pub fn workflow_runs(&self) -> &WorkflowRuns {
+        &self.runs
+    }
+
+    /// Version-controllable workflow definitions on disk
+    pub fn workflow_library(&self) -> &WorkflowLibrary {
+        &self.library
+    }
+
     /// Close an office window
     pub async fn close_office_window(&self, window_id: &str) -> Result<(), String> {
-        // Remove from tracking
-        let mut windows = self.windows.write().await;
-        windows.remove(window_id);
+        // Remove from tracking (the Destroyed event that follows is then a no-op)
+        self.tracker.forget(window_id).await;
 
         // Close the actual window
         if let Some(window) = self.app_handle.get_window(window_id) {
-            window.close().map_err(|e| format!("Failed to close window: {}", e))?;
+            window
+                .close()
+                .map_err(|e| format!("Failed to close window: {}", e))?;
         }
 
         Ok(())
@@ -261,6 +651,31 @@ impl WindowManager {
         windows.values().cloned().collect()
     }
 
+    /// Active office windows plus per-type instance counts
+    pub async fn office_inventory(&self) -> OfficeInventory {
+        let mut windows = self.get_active_offices().await;
+        windows.sort_by(|a, b| a.opened_at.total_cmp(&b.opened_at));
+
+        let mut counts: Vec<(OfficeType, usize)> = Vec::new();
+        for window in &windows {
+            match counts.iter_mut().find(|(t, _)| *t == window.office_type) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((window.office_type.clone(), 1)),
+            }
+        }
+
+        let mut instances = Vec::with_capacity(counts.len());
+        for (office_type, count) in counts {
+            instances.push(OfficeInstances {
+                policy: self.instance_policy(&office_type).await,
+                office_type,
+                count,
+            });
+        }
+
+        OfficeInventory { windows, instances }
+    }
+
     /// Send a message to a specific office
     pub async fn send_to_office(
         &self,
@@ -307,6 +722,14 @@ impl WindowManager {
 
         if let Some(office_window) = windows.get_mut(window_id) {
             office_window.memory_consent = consent;
+            drop(windows);
+
+            // Withdrawn consent ends any shares already granted
+            if !consent {
+                self.memory
+                    .revoke_window(window_id, "consent withdrawn")
+                    .await;
+            }
 
             // Notify the window of the consent update
             if let Some(window) = self.app_handle.get_window(window_id) {
@@ -321,66 +744,107 @@ impl WindowManager {
         }
     }
 
-    /// Update TTL for shared memory
+    /// Update TTL for shared memory; live grants held by or over the window are
+    /// re-timed from their grant time, so a shorter TTL can end them immediately
     pub async fn update_memory_ttl(&self, window_id: &str, ttl: u64) -> Result<(), String> {
         let mut windows = self.windows.write().await;
 
         if let Some(office_window) = windows.get_mut(window_id) {
             office_window.shared_memory_ttl = ttl;
+            drop(windows);
+
+            self.memory.apply_ttl(window_id).await;
             Ok(())
         } else {
             Err("Window not found".to_string())
         }
     }
 
-    /// Orchestrate a multi-office workflow
-    pub async fn orchestrate_workflow(
-        &self,
-        workflow: WorkflowDefinition,
-    ) -> Result<String, String> {
-        let workflow_id = Uuid::new_v4().to_string();
+    /// Save the currently open offices under a layout name
+    pub async fn save_layout(&self, name: &str) -> Result<OfficeLayout, String> {
+        let offices: Vec<OfficeLayoutEntry> = self
+            .windows
+            .read()
+            .await
+            .values()
+            .map(OfficeLayoutEntry::from)
+            .collect();
+
+        self.layouts.write().await.save_named(name, offices)
+    }
 
-        // Execute workflow steps
-        for step in workflow.steps {
-            match step.action {
-                WorkflowAction::OpenOffice(office_type) => {
-                    self.create_office_window(office_type, true, None).await?;
-                }
-                WorkflowAction::SendMessage(office_type, message) => {
-                    self.send_to_office(&office_type, message).await?;
-                }
-                WorkflowAction::WaitForResponse(timeout) => {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(timeout)).await;
-                }
-                WorkflowAction::CloseOffice(window_id) => {
-                    self.close_office_window(&window_id).await?;
-                }
-            }
-        }
+    pub async fn list_layouts(&self) -> Vec<OfficeLayout> {
+        self.layouts.read().await.list_named()
+    }
 
-        Ok(workflow_id)
+    pub async fn delete_layout(&self, name: &str) -> Result<(), String> {
+        self.layouts.write().await.delete_named(name)
     }
-}
 
-/// Workflow definition for multi-office orchestration
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WorkflowDefinition {
-    pub name: String,
-    pub steps: Vec<WorkflowStep>,
-}
+    /// Stop recording the session; called as the app exits so the last
+    /// session keeps the offices that were open rather than none
+    pub fn suspend_session(&self) {
+        self.session.suspend();
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WorkflowStep {
-    pub action: WorkflowAction,
-    pub description: String,
-}
+    /// The workspace that was open when the app last ran, if any
+    pub async fn previous_workspace(&self) -> Option<OfficeLayout> {
+        self.layouts.read().await.last_session().cloned()
+    }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum WorkflowAction {
-    OpenOffice(OfficeType),
-    SendMessage(OfficeType, serde_json::Value),
-    WaitForResponse(u64),
-    CloseOffice(String),
+    /// Reopen every office of a named layout (or the previous session)
+    pub async fn restore_layout(&self, name: Option<&str>) -> Result<Vec<String>, String> {
+        let layout = {
+            let layouts = self.layouts.read().await;
+            match name {
+                Some(name) => layouts
+                    .get_named(name)
+                    .cloned()
+                    .ok_or_else(|| format!("Layout not found: {}", name))?,
+                None => layouts
+                    .last_session()
+                    .cloned()
+                    .ok_or_else(|| "No previous workspace to restore".to_string())?,
+            }
+        };
+
+        let mut window_ids = Vec::with_capacity(layout.offices.len());
+        for entry in &layout.offices {
+            let opened = self
+                .open_office(
+                    entry.office_type.clone(),
+                    entry.memory_consent,
+                    Some(entry.shared_memory_ttl),
+                    Some(entry),
+                )
+                .await;
+
+            // An office that is already open (and may not be opened twice)
+            // is simply left as it is
+            let window_id = match opened {
+                Ok(opened) => opened.window_id,
+                Err(e) => {
+                    if self
+                        .find_office_windows(&entry.office_type)
+                        .await
+                        .is_empty()
+                    {
+                        return Err(e);
+                    }
+                    eprintln!(
+                        "[Unity] Skipping {} while restoring layout: {}",
+                        entry.office_type, e
+                    );
+                    continue;
+                }
+            };
+            if !window_ids.contains(&window_id) {
+                window_ids.push(window_id);
+            }
+        }
+
+        Ok(window_ids)
+    }
 }
 
 /// Tauri commands for window management
@@ -394,7 +858,9 @@ pub async fn create_office(
         .map_err(|e| format!("Invalid office type: {}", e))?;
 
     let manager = state.read().await;
-    manager.create_office_window(office_type, memory_consent, None).await
+    manager
+        .create_office_window(office_type, memory_consent, None)
+        .await
 }
 
 #[tauri::command]
@@ -409,9 +875,22 @@ pub async fn close_office(
 #[tauri::command]
 pub async fn get_offices(
     state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
-) -> Result<Vec<OfficeWindow>, String> {
+) -> Result<OfficeInventory, String> {
+    let manager = state.read().await;
+    Ok(manager.office_inventory().await)
+}
+
+#[tauri::command]
+pub async fn set_office_instance_policy(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    office_type: String,
+    policy: Option<InstancePolicy>,
+) -> Result<(), String> {
+    let office_type = serde_json::from_str::<OfficeType>(&format!("\"{}\"", office_type))
+        .map_err(|e| format!("Invalid office type: {}", e))?;
+
     let manager = state.read().await;
-    Ok(manager.get_active_offices().await)
+    manager.set_instance_policy(office_type, policy).await
 }
 
 #[tauri::command]
@@ -434,4 +913,23 @@ pub async fn broadcast_message(
 ) -> Result<(), String> {
     let manager = state.read().await;
     manager.broadcast_to_all(message).await
-}
\ No newline at end of file
+}
+#[tauri::command]
+pub async fn update_office_memory_consent(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    window_id: String,
+    consent: bool,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.update_memory_consent(&window_id, consent).await
+}
+
+#[tauri::command]
+pub async fn update_office_memory_ttl(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    window_id: String,
+    ttl: u64,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.update_memory_ttl(&window_id, ttl).await
+}
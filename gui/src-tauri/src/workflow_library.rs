@@ -0,0 +1,592 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::office_workflow::{
+    start_workflow, OfficeRef, OnError, WorkflowAction, WorkflowDefinition, WorkflowStep,
+};
+use crate::util::write_atomic;
+use crate::window_manager::WindowManager;
+
+/// Directory (inside the app data dir) holding workflow definitions
+const LIBRARY_DIR: &str = "workflows";
+
+/// Overrides the library location, e.g. to point at a version-controlled playbook repo
+const LIBRARY_DIR_ENV: &str = "UNITY_WORKFLOW_LIBRARY";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionFormat {
+    Yaml,
+    Json,
+}
+
+impl DefinitionFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "yaml" | "yml" => Some(Self::Yaml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Yaml => "yaml",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// A single problem found while loading a definition. `location` is a parser
+/// position (`line 4 column 7`), a field path (`steps[1].action`) or a step
+/// path (`steps.2.then.0`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationIssue {
+    pub location: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationReport {
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+    pub definition: Option<WorkflowDefinition>,
+}
+
+/// A definition file in the library
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryEntry {
+    pub id: String,
+    pub file: String,
+    pub format: DefinitionFormat,
+    pub name: Option<String>,
+    pub step_count: usize,
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// Parse and validate a definition from source text. YAML is read with the
+/// same map-style enum layout as JSON (`OpenOffice: LegalOffice`), so one
+/// playbook can be converted between the two formats losslessly.
+pub fn parse_definition(source: &str, format: DefinitionFormat) -> ValidationReport {
+    let document = match format {
+        DefinitionFormat::Json => {
+            serde_json::from_str::<serde_json::Value>(source).map_err(|e| ValidationIssue {
+                location: Some(format!("line {} column {}", e.line(), e.column())),
+                message: e.to_string(),
+            })
+        }
+        DefinitionFormat::Yaml => {
+            serde_yaml::from_str::<serde_json::Value>(source).map_err(|e| ValidationIssue {
+                location: e
+                    .location()
+                    .map(|l| format!("line {} column {}", l.line(), l.column())),
+                message: e.to_string(),
+            })
+        }
+    };
+
+    // Deserialize via the JSON value so errors name the offending field,
+    // e.g. `steps[2].action.OpenOffice: unknown variant `Legal``
+    let parsed = document.and_then(|document| {
+        serde_path_to_error::deserialize::<_, WorkflowDefinition>(document).map_err(|e| {
+            ValidationIssue {
+                location: Some(e.path().to_string()),
+                message: e.inner().to_string(),
+            }
+        })
+    });
+
+    match parsed {
+        Ok(definition) => {
+            let issues = validate_definition(&definition);
+            ValidationReport {
+                valid: issues.is_empty(),
+                issues,
+                definition: Some(definition),
+            }
+        }
+        Err(issue) => ValidationReport {
+            valid: false,
+            issues: vec![issue],
+            definition: None,
+        },
+    }
+}
+
+/// Structural checks that serde cannot express
+pub fn validate_definition(definition: &WorkflowDefinition) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if definition.name.trim().is_empty() {
+        issues.push(ValidationIssue {
+            location: Some("name".to_string()),
+            message: "Workflow name cannot be empty".to_string(),
+        });
+    }
+    if definition.steps.is_empty() {
+        issues.push(ValidationIssue {
+            location: Some("steps".to_string()),
+            message: "Workflow has no steps".to_string(),
+        });
+    }
+
+    validate_steps(&definition.steps, "steps", false, &mut issues);
+    issues
+}
+
+/// Check a block of steps. `has_pending_request` says whether a `SendMessage`
+/// before the block may still be awaiting its reply; the state after the block
+/// is returned so nested blocks share it with the steps around them.
+fn validate_steps(
+    steps: &[WorkflowStep],
+    prefix: &str,
+    mut has_pending_request: bool,
+    issues: &mut Vec<ValidationIssue>,
+) -> bool {
+    for (index, step) in steps.iter().enumerate() {
+        let path = format!("{}.{}", prefix, index);
+        let mut issue = |message: String| {
+            issues.push(ValidationIssue {
+                location: Some(path.clone()),
+                message,
+            })
+        };
+
+        if let Some(name) = &step.bind {
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                issue(format!("Invalid variable name '{}'", name));
+            }
+        }
+
+        match &step.action {
+            WorkflowAction::SendMessage(..) => has_pending_request = true,
+            WorkflowAction::WaitForResponse { timeout_secs, .. } => {
+                if !has_pending_request {
+                    issue("WaitForResponse must follow a SendMessage".to_string());
+                }
+                if *timeout_secs == 0 {
+                    issue("timeout_secs must be greater than zero".to_string());
+                }
+                has_pending_request = false;
+            }
+            WorkflowAction::Ask { timeout_secs, .. } => {
+                if *timeout_secs == 0 {
+                    issue("timeout_secs must be greater than zero".to_string());
+                }
+                has_pending_request = false;
+            }
+            WorkflowAction::SetVariable { name, .. } => {
+                if name.is_empty() {
+                    issue("SetVariable needs a variable name".to_string());
+                }
+            }
+            WorkflowAction::CloseOffice(office_ref) => {
+                let name = match office_ref {
+                    OfficeRef::Alias { alias } => Some(alias),
+                    OfficeRef::Window { window_id } => Some(window_id),
+                    OfficeRef::Name(name) => Some(name),
+                    OfficeRef::Office { .. } => None,
+                };
+                if name.is_some_and(|n| n.trim().is_empty()) {
+                    issue("CloseOffice needs an office, alias or window id".to_string());
+                }
+            }
+            WorkflowAction::OpenOffice(spec) => {
+                if spec.alias.as_ref().is_some_and(|a| a.trim().is_empty()) {
+                    issue("OpenOffice alias cannot be empty".to_string());
+                }
+            }
+            WorkflowAction::Branch {
+                then, otherwise, ..
+            } => {
+                // Either arm may run, so a request left pending by either one counts
+                let then_pending =
+                    validate_steps(then, &format!("{}.then", path), has_pending_request, issues);
+                let otherwise_pending = validate_steps(
+                    otherwise,
+                    &format!("{}.otherwise", path),
+                    has_pending_request,
+                    issues,
+                );
+                has_pending_request = then_pending || otherwise_pending;
+            }
+            WorkflowAction::Parallel { branches } => {
+                if branches.is_empty() {
+                    issue("Parallel needs at least one branch".to_string());
+                }
+                // Branches start without a pending request and never hand one back
+                for (i, branch) in branches.iter().enumerate() {
+                    validate_steps(branch, &format!("{}.branch{}", path, i), false, issues);
+                }
+            }
+        }
+
+        if let OnError::Run(handler) = &step.on_error {
+            has_pending_request |= validate_steps(
+                handler,
+                &format!("{}.on_error", path),
+                has_pending_request,
+                issues,
+            );
+        }
+    }
+
+    has_pending_request
+}
+
+/// Library ids are file stems; anything else could name a file outside the library
+fn validate_id(id: &str) -> Result<(), String> {
+    let allowed = |c: char| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-');
+    if id.is_empty() || id.contains("..") || !id.chars().all(allowed) {
+        return Err(format!("Invalid workflow id: {:?}", id));
+    }
+    Ok(())
+}
+
+/// Workflow definitions stored as YAML/JSON files in one directory
+pub struct WorkflowLibrary {
+    dir: Option<PathBuf>,
+}
+
+impl WorkflowLibrary {
+    pub fn new(app_handle: &tauri::AppHandle) -> Self {
+        let dir = std::env::var_os(LIBRARY_DIR_ENV)
+            .map(PathBuf::from)
+            .or_else(|| {
+                app_handle
+                    .path_resolver()
+                    .app_data_dir()
+                    .map(|dir| dir.join(LIBRARY_DIR))
+            });
+        Self { dir }
+    }
+
+    fn dir(&self) -> Result<&Path, String> {
+        self.dir
+            .as_deref()
+            .ok_or_else(|| "No workflow library directory available".to_string())
+    }
+
+    fn entry_for(path: &Path) -> Option<LibraryEntry> {
+        let format = DefinitionFormat::from_path(path)?;
+        let id = path.file_stem()?.to_str()?.to_string();
+        let file = path.file_name()?.to_str()?.to_string();
+
+        let report = match std::fs::read_to_string(path) {
+            Ok(source) => parse_definition(&source, format),
+            Err(e) => ValidationReport {
+                valid: false,
+                issues: vec![ValidationIssue {
+                    location: None,
+                    message: format!("Failed to read file: {}", e),
+                }],
+                definition: None,
+            },
+        };
+
+        Some(LibraryEntry {
+            id,
+            file,
+            format,
+            name: report.definition.as_ref().map(|d| d.name.clone()),
+            step_count: report.definition.as_ref().map_or(0, |d| d.steps.len()),
+            valid: report.valid,
+            issues: report.issues,
+        })
+    }
+
+    /// Every definition file in the library, valid or not
+    pub fn list(&self) -> Result<Vec<LibraryEntry>, String> {
+        let dir = self.dir()?;
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries: Vec<LibraryEntry> = std::fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read workflow library: {}", e))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Self::entry_for(&entry.path()))
+            .collect();
+        entries.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(entries)
+    }
+
+    fn find(&self, id: &str) -> Result<PathBuf, String> {
+        validate_id(id)?;
+        let dir = self.dir()?;
+        ["yaml", "yml", "json"]
+            .iter()
+            .map(|ext| dir.join(format!("{}.{}", id, ext)))
+            .find(|path| path.exists())
+            .ok_or_else(|| format!("Workflow definition not found: {}", id))
+    }
+
+    /// Load and validate a definition by id (file stem)
+    pub fn load(&self, id: &str) -> Result<WorkflowDefinition, String> {
+        let path = self.find(id)?;
+        let format = DefinitionFormat::from_path(&path)
+            .ok_or_else(|| format!("Unsupported workflow file: {}", path.display()))?;
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        let report = parse_definition(&source, format);
+        match report.definition {
+            Some(definition) if report.valid => Ok(definition),
+            _ => Err(format_issues(&path, &report.issues)),
+        }
+    }
+
+    /// Copy a definition file into the library after validating it. An existing
+    /// definition with the same id is only replaced when `overwrite` is set.
+    pub fn import(&self, source_path: &Path, overwrite: bool) -> Result<LibraryEntry, String> {
+        let format = DefinitionFormat::from_path(source_path).ok_or_else(|| {
+            format!(
+                "Unsupported workflow file (expected .yaml, .yml or .json): {}",
+                source_path.display()
+            )
+        })?;
+        let source = std::fs::read_to_string(source_path)
+            .map_err(|e| format!("Failed to read {}: {}", source_path.display(), e))?;
+
+        let report = parse_definition(&source, format);
+        if !report.valid {
+            return Err(format_issues(source_path, &report.issues));
+        }
+
+        let file_name = source_path
+            .file_name()
+            .ok_or_else(|| format!("Invalid path: {}", source_path.display()))?;
+        let id = source_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("Invalid path: {}", source_path.display()))?;
+        validate_id(id)?;
+
+        let existing = self.find(id).ok();
+        if let (Some(existing), false) = (&existing, overwrite) {
+            return Err(format!(
+                "Workflow definition already exists: {} ({})",
+                id,
+                existing.display()
+            ));
+        }
+
+        let dir = self.dir()?;
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create workflow library: {}", e))?;
+
+        let target = dir.join(file_name);
+        write_atomic(&target, source)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+
+        // The id may have existed under another extension; once the new file
+        // is in place, remove that one so the id keeps naming a single definition
+        if let Some(existing) = existing.filter(|existing| *existing != target) {
+            std::fs::remove_file(&existing)
+                .map_err(|e| format!("Failed to replace {}: {}", existing.display(), e))?;
+        }
+
+        Self::entry_for(&target).ok_or_else(|| format!("Failed to index {}", target.display()))
+    }
+
+    /// Write a library definition to `target`, in the format its extension names
+    pub fn export(&self, id: &str, target: &Path) -> Result<(), String> {
+        let definition = self.load(id)?;
+        let format = DefinitionFormat::from_path(target).unwrap_or(DefinitionFormat::Yaml);
+        let target = if DefinitionFormat::from_path(target).is_some() {
+            target.to_path_buf()
+        } else {
+            target.with_extension(format.extension())
+        };
+
+        let text = match format {
+            DefinitionFormat::Yaml => serde_json::to_value(&definition)
+                .map_err(|e| e.to_string())
+                .and_then(|value| serde_yaml::to_string(&value).map_err(|e| e.to_string()))
+                .map_err(|e| format!("Failed to serialize workflow: {}", e))?,
+            DefinitionFormat::Json => serde_json::to_string_pretty(&definition)
+                .map_err(|e| format!("Failed to serialize workflow: {}", e))?,
+        };
+
+        std::fs::write(&target, text)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))
+    }
+}
+
+fn format_issues(path: &Path, issues: &[ValidationIssue]) -> String {
+    let details: Vec<String> = issues
+        .iter()
+        .map(|i| match &i.location {
+            Some(location) => format!("{}: {}", location, i.message),
+            None => i.message.clone(),
+        })
+        .collect();
+    format!(
+        "Invalid workflow {}: {}",
+        path.display(),
+        details.join("; ")
+    )
+}
+
+/// Tauri commands for the workflow library
+#[tauri::command]
+pub async fn list_workflow_definitions(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Vec<LibraryEntry>, String> {
+    let manager = state.read().await;
+    manager.workflow_library().list()
+}
+
+#[tauri::command]
+pub async fn get_workflow_definition(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    id: String,
+) -> Result<WorkflowDefinition, String> {
+    let manager = state.read().await;
+    manager.workflow_library().load(&id)
+}
+
+#[tauri::command]
+pub async fn validate_workflow_definition(
+    source: String,
+    format: DefinitionFormat,
+) -> Result<ValidationReport, String> {
+    Ok(parse_definition(&source, format))
+}
+
+#[tauri::command]
+pub async fn import_workflow_definition(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    path: String,
+    overwrite: Option<bool>,
+) -> Result<LibraryEntry, String> {
+    let manager = state.read().await;
+    manager
+        .workflow_library()
+        .import(Path::new(&path), overwrite.unwrap_or(false))
+}
+
+#[tauri::command]
+pub async fn export_workflow_definition(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    id: String,
+    path: String,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.workflow_library().export(&id, Path::new(&path))
+}
+
+#[tauri::command]
+pub async fn run_library_workflow(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    id: String,
+) -> Result<String, String> {
+    let definition = state.read().await.workflow_library().load(&id)?;
+    start_workflow(state.inner().clone(), definition).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(yaml: &str) -> Vec<String> {
+        let report = parse_definition(yaml, DefinitionFormat::Yaml);
+        report.issues.into_iter().map(|i| i.message).collect()
+    }
+
+    fn temp_library(name: &str) -> (WorkflowLibrary, PathBuf) {
+        let root = std::env::temp_dir().join(format!("unity-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::create_dir_all(&root).unwrap();
+        let library = WorkflowLibrary {
+            dir: Some(root.join("library")),
+        };
+        (library, root)
+    }
+
+    const MINIMAL: &str = "name: Review\nsteps:\n  - action:\n      OpenOffice: LegalOffice\n";
+
+    #[test]
+    fn wait_inside_a_branch_sees_the_request_sent_before_it() {
+        let yaml = r#"
+name: Review
+steps:
+  - action:
+      SendMessage: [LegalOffice, {text: review}]
+  - action:
+      Branch:
+        condition: {exists: {var: urgent}}
+        then:
+          - action:
+              WaitForResponse: {timeout_secs: 30}
+"#;
+        assert!(issues(yaml).is_empty(), "{:?}", issues(yaml));
+    }
+
+    #[test]
+    fn wait_inside_a_parallel_branch_needs_its_own_request() {
+        let yaml = r#"
+name: Review
+steps:
+  - action:
+      SendMessage: [LegalOffice, {text: review}]
+  - action:
+      Parallel:
+        branches:
+          - - action:
+                WaitForResponse: {timeout_secs: 30}
+"#;
+        assert_eq!(
+            issues(yaml),
+            vec!["WaitForResponse must follow a SendMessage"]
+        );
+    }
+
+    #[test]
+    fn rejects_ids_that_escape_the_library() {
+        for id in ["../secrets", "a/b", "..", "", "a\\b"] {
+            assert!(validate_id(id).is_err(), "{:?} accepted", id);
+        }
+        for id in ["review", "legal-review_v2", "review.v2"] {
+            assert!(validate_id(id).is_ok(), "{:?} rejected", id);
+        }
+
+        let (library, root) = temp_library("escape");
+        std::fs::write(root.join("outside.yaml"), MINIMAL).unwrap();
+        assert!(library.load("../outside").is_err());
+        std::fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn import_refuses_to_replace_an_existing_definition() {
+        let (library, root) = temp_library("import");
+        let yaml = root.join("review.yaml");
+        let json = root.join("review.json");
+        std::fs::write(&yaml, MINIMAL).unwrap();
+        std::fs::write(
+            &json,
+            r#"{"name": "Review v2", "steps": [{"action": {"OpenOffice": "LegalOffice"}}]}"#,
+        )
+        .unwrap();
+
+        library.import(&yaml, false).unwrap();
+        assert!(library.import(&json, false).is_err());
+        assert_eq!(library.load("review").unwrap().name, "Review");
+
+        library.import(&json, true).unwrap();
+        let entries = library.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name.as_deref(), Some("Review v2"));
+
+        // Replacing a file with one of the same name keeps the new copy
+        library.import(&yaml, true).unwrap();
+        library.import(&yaml, true).unwrap();
+        let entries = library.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name.as_deref(), Some("Review"));
+        std::fs::remove_dir_all(root).ok();
+    }
+}
@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::Manager;
+use tokio::sync::{watch, RwLock};
+use uuid::Uuid;
+
+use crate::office_workflow::WorkflowDefinition;
+use crate::util::now_secs;
+use crate::window_manager::WindowManager;
+
+/// Error returned by a workflow that was cancelled; never handled by `on_error`
+pub const CANCELLED: &str = "Workflow cancelled";
+
+/// Finished runs kept for inspection before the oldest are pruned
+const MAX_FINISHED_RUNS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// Control signal observed by the executor between (and during) steps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunControl {
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Status and timing of one executed step. `path` locates the step within
+/// nested branches, e.g. `2.then.0` or `3.branch1.0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub path: String,
+    pub label: String,
+    pub action: String,
+    pub status: StepStatus,
+    pub started_at: f64,
+    pub finished_at: Option<f64>,
+    pub duration_ms: Option<f64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowRun {
+    pub id: String,
+    pub name: String,
+    pub status: RunStatus,
+    pub started_at: f64,
+    pub finished_at: Option<f64>,
+    pub error: Option<String>,
+    pub steps: Vec<StepRecord>,
+}
+
+/// Payload of `unity:office_workflow_step`
+#[derive(Debug, Clone, Serialize)]
+struct StepEvent<'a> {
+    run_id: &'a str,
+    step: &'a StepRecord,
+}
+
+struct RunEntry {
+    run: WorkflowRun,
+    control: watch::Sender<RunControl>,
+}
+
+/// Registry of background workflow runs
+#[derive(Clone)]
+pub struct WorkflowRuns {
+    app_handle: tauri::AppHandle,
+    runs: Arc<RwLock<HashMap<String, RunEntry>>>,
+}
+
+impl WorkflowRuns {
+    pub fn new(app_handle: tauri::AppHandle) -> Self {
+        Self {
+            app_handle,
+            runs: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Register a new run and hand back its id and control receiver
+    pub async fn register(
+        &self,
+        workflow: &WorkflowDefinition,
+    ) -> (String, watch::Receiver<RunControl>) {
+        let run_id = Uuid::new_v4().to_string();
+        let (control, control_rx) = watch::channel(RunControl::Run);
+
+        let mut runs = self.runs.write().await;
+        Self::prune(&mut runs);
+        runs.insert(
+            run_id.clone(),
+            RunEntry {
+                run: WorkflowRun {
+                    id: run_id.clone(),
+                    name: workflow.name.clone(),
+                    status: RunStatus::Running,
+                    started_at: now_secs(),
+                    finished_at: None,
+                    error: None,
+                    steps: Vec::new(),
+                },
+                control,
+            },
+        );
+
+        (run_id, control_rx)
+    }
+
+    fn prune(runs: &mut HashMap<String, RunEntry>) {
+        let mut finished: Vec<(String, f64)> = runs
+            .values()
+            .filter_map(|e| e.run.finished_at.map(|t| (e.run.id.clone(), t)))
+            .collect();
+        if finished.len() < MAX_FINISHED_RUNS {
+            return;
+        }
+        finished.sort_by(|a, b| a.1.total_cmp(&b.1));
+        for (id, _) in finished.iter().take(finished.len() + 1 - MAX_FINISHED_RUNS) {
+            runs.remove(id);
+        }
+    }
+
+    pub async fn step_started(&self, run_id: &str, path: &str, label: &str, action: &str) {
+        let record = StepRecord {
+            path: path.to_string(),
+            label: label.to_string(),
+            action: action.to_string(),
+            status: StepStatus::Running,
+            started_at: now_secs(),
+            finished_at: None,
+            duration_ms: None,
+            error: None,
+        };
+
+        self.emit_step(run_id, &record);
+        if let Some(entry) = self.runs.write().await.get_mut(run_id) {
+            entry.run.steps.push(record);
+        }
+    }
+
+    pub async fn step_finished(&self, run_id: &str, path: &str, error: Option<&str>) {
+        let mut runs = self.runs.write().await;
+        let Some(entry) = runs.get_mut(run_id) else {
+            return;
+        };
+        let Some(record) = entry.run.steps.iter_mut().rev().find(|s| s.path == path) else {
+            return;
+        };
+
+        let finished_at = now_secs();
+        record.finished_at = Some(finished_at);
+        record.duration_ms = Some((finished_at - record.started_at) * 1000.0);
+        record.status = if error.is_some() {
+            StepStatus::Failed
+        } else {
+            StepStatus::Succeeded
+        };
+        record.error = error.map(str::to_string);
+
+        self.emit_step(run_id, record);
+    }
+
+    fn emit_step(&self, run_id: &str, step: &StepRecord) {
+        self.app_handle
+            .emit_all("unity:office_workflow_step", StepEvent { run_id, step })
+            .ok();
+    }
+
+    /// Record the final outcome of a run and announce it with
+    /// `unity:workflow_run_finished`
+    pub async fn finish(&self, run_id: &str, result: &Result<(), String>) {
+        if let Some(entry) = self.runs.write().await.get_mut(run_id) {
+            entry.run.finished_at = Some(now_secs());
+            entry.run.status = match result {
+                Ok(()) => RunStatus::Completed,
+                Err(e) if e == CANCELLED => RunStatus::Cancelled,
+                Err(_) => RunStatus::Failed,
+            };
+            entry.run.error = result.as_ref().err().cloned();
+            self.app_handle
+                .emit_all("unity:workflow_run_finished", &entry.run)
+                .ok();
+        }
+    }
+
+    pub async fn list(&self) -> Vec<WorkflowRun> {
+        let mut runs: Vec<WorkflowRun> = self
+            .runs
+            .read()
+            .await
+            .values()
+            .map(|e| e.run.clone())
+            .collect();
+        runs.sort_by(|a, b| b.started_at.total_cmp(&a.started_at));
+        runs
+    }
+
+    pub async fn get(&self, run_id: &str) -> Option<WorkflowRun> {
+        self.runs.read().await.get(run_id).map(|e| e.run.clone())
+    }
+
+    async fn signal(&self, run_id: &str, control: RunControl) -> Result<(), String> {
+        let mut runs = self.runs.write().await;
+        let entry = runs
+            .get_mut(run_id)
+            .ok_or_else(|| format!("Workflow run not found: {}", run_id))?;
+
+        if entry.run.finished_at.is_some() {
+            return Err(format!("Workflow run already finished: {}", run_id));
+        }
+
+        entry.control.send_replace(control);
+        match control {
+            RunControl::Run => entry.run.status = RunStatus::Running,
+            RunControl::Pause => entry.run.status = RunStatus::Paused,
+            RunControl::Cancel => {}
+        }
+        Ok(())
+    }
+
+    pub async fn pause(&self, run_id: &str) -> Result<(), String> {
+        self.signal(run_id, RunControl::Pause).await
+    }
+
+    pub async fn resume(&self, run_id: &str) -> Result<(), String> {
+        self.signal(run_id, RunControl::Run).await
+    }
+
+    pub async fn cancel(&self, run_id: &str) -> Result<(), String> {
+        self.signal(run_id, RunControl::Cancel).await
+    }
+}
+
+/// Block while paused; fail once cancelled
+pub async fn checkpoint(control: &mut watch::Receiver<RunControl>) -> Result<(), String> {
+    loop {
+        let state = *control.borrow_and_update();
+        match state {
+            RunControl::Run => return Ok(()),
+            RunControl::Cancel => return Err(CANCELLED.to_string()),
+            RunControl::Pause => {
+                if control.changed().await.is_err() {
+                    return Err(CANCELLED.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// Resolve once the run is cancelled (used to interrupt long-running steps)
+pub async fn cancelled(mut control: watch::Receiver<RunControl>) {
+    loop {
+        if *control.borrow_and_update() == RunControl::Cancel {
+            return;
+        }
+        if control.changed().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+/// Tauri commands for workflow runs
+#[tauri::command]
+pub async fn list_workflow_runs(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+) -> Result<Vec<WorkflowRun>, String> {
+    let manager = state.read().await;
+    Ok(manager.workflow_runs().list().await)
+}
+
+#[tauri::command]
+pub async fn get_workflow_run(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    run_id: String,
+) -> Result<WorkflowRun, String> {
+    let manager = state.read().await;
+    manager
+        .workflow_runs()
+        .get(&run_id)
+        .await
+        .ok_or_else(|| format!("Workflow run not found: {}", run_id))
+}
+
+#[tauri::command]
+pub async fn pause_workflow_run(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    run_id: String,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.workflow_runs().pause(&run_id).await
+}
+
+#[tauri::command]
+pub async fn resume_workflow_run(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    run_id: String,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.workflow_runs().resume(&run_id).await
+}
+
+#[tauri::command]
+pub async fn cancel_workflow_run(
+    state: tauri::State<'_, Arc<RwLock<WindowManager>>>,
+    run_id: String,
+) -> Result<(), String> {
+    let manager = state.read().await;
+    manager.workflow_runs().cancel(&run_id).await
+}